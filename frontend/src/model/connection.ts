@@ -1,3 +1,4 @@
+import { applyPatch } from "./patch";
 import { getStoredValue, setStoredValue } from "./storage";
 import { GameStateView, Task, TaskAction, User } from "./types";
 
@@ -15,6 +16,10 @@ export class Connection {
   state: null | GameStateView;
   task: null | Task;
 
+  // The last state received, which the server's diffs apply to.
+  version: number;
+  doc: any;
+
   constructor(ws: WebSocket, rerender: RenderFn) {
     this.ws = ws;
     this.rerender = rerender;
@@ -23,6 +28,9 @@ export class Connection {
     this.state = null;
     this.task = null;
 
+    this.version = -1;
+    this.doc = null;
+
     this.ws.onopen = () => this.onOpen();
     this.ws.onmessage = (msg) => this.onMessage(msg);
   }
@@ -34,6 +42,7 @@ export class Connection {
         JSON.stringify({
           Authenticate: {
             access_key: key,
+            diffs: true,
           },
         }),
       );
@@ -44,10 +53,22 @@ export class Connection {
     const data = JSON.parse(msg.data);
     switch (data.type) {
       case "State":
-        if (this.authenticated) {
-          this.state = data.value.game_state;
-          this.task = data.value.task;
-          this.rerender(this.state!, this.task, this.authenticated);
+        this.doc = data.value;
+        this.version = data.value.version;
+        this.onState();
+        break;
+      case "Diff":
+        if (
+          this.doc !== null &&
+          data.value.base === this.version &&
+          applyPatch(this.doc, data.value.patch)
+        ) {
+          this.version = data.value.version;
+          this.onState();
+        } else {
+          // Out of sync, the server answers with the full state.
+          this.doc = null;
+          this.ack();
         }
         break;
       case "Authenticated":
@@ -59,11 +80,26 @@ export class Connection {
     }
   }
 
+  onState() {
+    this.ack();
+    if (this.authenticated) {
+      // Copies, since the document is patched in place.
+      this.state = structuredClone(this.doc.game_state);
+      this.task = structuredClone(this.doc.task);
+      this.rerender(this.state!, this.task, this.authenticated);
+    }
+  }
+
+  ack() {
+    this.ws.send(JSON.stringify({ Ack: this.version }));
+  }
+
   login(user: User) {
     this.ws.send(
       JSON.stringify({
         Authenticate: {
           user,
+          diffs: true,
         },
       }),
     );
@@ -0,0 +1,50 @@
+export type PatchOp =
+  | { op: "add"; path: string; value: any }
+  | { op: "remove"; path: string }
+  | { op: "replace"; path: string; value: any };
+
+function parsePath(path: string): string[] {
+  return path
+    .split("/")
+    .slice(1)
+    .map((key) => key.replace(/~1/g, "/").replace(/~0/g, "~"));
+}
+
+// Applies a JSON patch as sent by the server in place. Returns false if the
+// patch does not fit the document.
+export function applyPatch(doc: any, patch: PatchOp[]): boolean {
+  for (const op of patch) {
+    const keys = parsePath(op.path);
+    const last = keys.pop();
+    if (last === undefined) {
+      return false;
+    }
+
+    let parent = doc;
+    for (const key of keys) {
+      if (parent === null || typeof parent !== "object" || !(key in parent)) {
+        return false;
+      }
+      parent = parent[key];
+    }
+    if (parent === null || typeof parent !== "object") {
+      return false;
+    }
+
+    if (Array.isArray(parent)) {
+      const index = last === "-" ? parent.length : Number(last);
+      if (op.op === "add") {
+        parent.splice(index, 0, op.value);
+      } else if (op.op === "remove") {
+        parent.splice(index, 1);
+      } else {
+        parent[index] = op.value;
+      }
+    } else if (op.op === "remove") {
+      delete parent[last];
+    } else {
+      parent[last] = op.value;
+    }
+  }
+  return true;
+}
@@ -54,6 +54,9 @@ export interface PlayerView {
 
   hasTask: boolean;
   access_key: string;
+
+  connected: boolean;
+  last_seen: null | number;
 }
 
 export type PlayerId = string;
@@ -112,4 +115,7 @@ export interface Event {
     president: PlayerId;
     chancellor: PlayerId;
   };
+  Chaos?: {
+    law: Law;
+  };
 }
@@ -0,0 +1,251 @@
+//! Plays many games between bots and reports balance statistics.
+//!
+//! Usage: `simulate [--games N] [--players MIN-MAX] [--seed S] [--format csv|json]`
+
+use std::{collections::BTreeMap, error::Error, ops::RangeInclusive};
+
+use rand::{rngs::StdRng, SeedableRng};
+use secret_hitler::{
+    bot, Config, Event, ExecutiveActionResponse, GameState, LogEntry, SpectatorMode, TaskAction,
+    Win, WinReason, MAX_PLAYERS, MIN_PLAYERS,
+};
+use serde::Serialize;
+
+#[derive(Debug)]
+struct Args {
+    games: usize,
+    players: RangeInclusive<usize>,
+    seed: u64,
+    json: bool,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct Stats {
+    players: usize,
+    games: usize,
+    unfinished: usize,
+    liberal_wins: usize,
+    fasho_wins: usize,
+    liberal_win_rate: f64,
+    win_reasons: BTreeMap<String, usize>,
+    avg_rounds: f64,
+    avg_laws: f64,
+    vetoes_per_game: f64,
+    chaos_per_game: f64,
+    executive_actions_per_game: BTreeMap<String, f64>,
+}
+
+fn parse_args() -> Result<Args, Box<dyn Error>> {
+    let mut args = Args {
+        games: 1000,
+        players: MIN_PLAYERS..=MAX_PLAYERS,
+        seed: 0,
+        json: false,
+    };
+
+    let mut iter = std::env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().ok_or(format!("missing value for {arg}"));
+        match arg.as_str() {
+            "--games" => args.games = value()?.parse()?,
+            "--seed" => args.seed = value()?.parse()?,
+            "--players" => {
+                let value = value()?;
+                args.players = match value.split_once('-') {
+                    Some((min, max)) => min.parse()?..=max.parse()?,
+                    None => {
+                        let n = value.parse()?;
+                        n..=n
+                    }
+                };
+            }
+            "--format" => {
+                args.json = match value()?.as_str() {
+                    "json" => true,
+                    "csv" => false,
+                    other => return Err(format!("unknown format {other:?}").into()),
+                }
+            }
+            other => return Err(format!("unknown argument {other:?}").into()),
+        }
+    }
+
+    if *args.players.start() < MIN_PLAYERS || *args.players.end() > MAX_PLAYERS {
+        return Err(
+            format!("player counts must be between {MIN_PLAYERS} and {MAX_PLAYERS}").into(),
+        );
+    }
+    Ok(args)
+}
+
+fn play(players: usize, seed: u64) -> GameState {
+    let mut game = GameState::new(Config {
+        seed: Some(seed),
+        ..Config::default()
+    });
+    let ids = (0..players)
+        .map(|_| game.add_bot(None).expect("lobby is open"))
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    game.on_action(ids[0].clone(), TaskAction::Start)
+        .expect("enough players to start");
+
+    bot::run_bots(&mut game, &mut StdRng::seed_from_u64(seed));
+    game
+}
+
+fn simulate(players: usize, args: &Args) -> Stats {
+    let mut stats = Stats {
+        players,
+        games: args.games,
+        ..Stats::default()
+    };
+    let mut rounds = 0;
+    let mut laws = 0;
+    let mut vetoes = 0;
+    let mut chaos = 0;
+    let mut executive_actions = BTreeMap::<String, usize>::new();
+
+    for i in 0..args.games {
+        let seed = args.seed.wrapping_add((players * args.games + i) as u64);
+        let game = play(players, seed);
+        let history = &game.spectator_view(SpectatorMode::Public).board.history;
+
+        let mut finished = false;
+        for event in history {
+            match event {
+                Event::ChooseChancellor { .. } => rounds += 1,
+                Event::PlayedLaw { .. } => laws += 1,
+                Event::Veto { .. } => vetoes += 1,
+                Event::Chaos { .. } => {
+                    laws += 1;
+                    chaos += 1;
+                }
+                Event::GameOver { winner, reason } => {
+                    finished = true;
+                    match winner {
+                        Win::Liberal => stats.liberal_wins += 1,
+                        Win::Fasho => stats.fasho_wins += 1,
+                    }
+                    *stats.win_reasons.entry(reason_name(*reason)).or_default() += 1;
+                }
+                _ => {}
+            }
+        }
+        if !finished {
+            stats.unfinished += 1;
+        }
+
+        for entry in &game.log().entries {
+            if let LogEntry::Action {
+                action: TaskAction::ExecuteAction(response),
+                ..
+            } = entry
+            {
+                *executive_actions
+                    .entry(action_name(response).to_string())
+                    .or_default() += 1;
+            }
+        }
+    }
+
+    let games = args.games.max(1) as f64;
+    let finished = (stats.liberal_wins + stats.fasho_wins).max(1) as f64;
+    stats.liberal_win_rate = stats.liberal_wins as f64 / finished;
+    stats.avg_rounds = rounds as f64 / games;
+    stats.avg_laws = laws as f64 / games;
+    stats.vetoes_per_game = vetoes as f64 / games;
+    stats.chaos_per_game = chaos as f64 / games;
+    stats.executive_actions_per_game = executive_actions
+        .into_iter()
+        .map(|(name, count)| (name, count as f64 / games))
+        .collect();
+    stats
+}
+
+fn reason_name(reason: WinReason) -> String {
+    format!("{reason:?}")
+}
+
+fn action_name(response: &ExecutiveActionResponse) -> &'static str {
+    match response {
+        ExecutiveActionResponse::Kill(_) => "Kill",
+        ExecutiveActionResponse::RevealFaction(_) => "RevealFaction",
+        ExecutiveActionResponse::DeterminePresident(_) => "DeterminePresident",
+        ExecutiveActionResponse::RevealNextCards => "RevealNextCards",
+    }
+}
+
+fn print_csv(stats: &[Stats]) {
+    const REASONS: [WinReason; 4] = [
+        WinReason::LiberalLaws,
+        WinReason::FashoLaws,
+        WinReason::HitlerKilled,
+        WinReason::HitlerElected,
+    ];
+    const ACTIONS: [&str; 4] = [
+        "Kill",
+        "RevealFaction",
+        "DeterminePresident",
+        "RevealNextCards",
+    ];
+
+    let mut header = vec![
+        "players",
+        "games",
+        "unfinished",
+        "liberal_wins",
+        "fasho_wins",
+        "liberal_win_rate",
+        "avg_rounds",
+        "avg_laws",
+        "vetoes_per_game",
+        "chaos_per_game",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect::<Vec<_>>();
+    header.extend(REASONS.iter().map(|r| format!("wins_{}", reason_name(*r))));
+    header.extend(ACTIONS.iter().map(|a| format!("{a}_per_game")));
+    println!("{}", header.join(","));
+
+    for s in stats {
+        let mut row = vec![
+            s.players.to_string(),
+            s.games.to_string(),
+            s.unfinished.to_string(),
+            s.liberal_wins.to_string(),
+            s.fasho_wins.to_string(),
+            format!("{:.4}", s.liberal_win_rate),
+            format!("{:.2}", s.avg_rounds),
+            format!("{:.2}", s.avg_laws),
+            format!("{:.4}", s.vetoes_per_game),
+            format!("{:.4}", s.chaos_per_game),
+        ];
+        row.extend(REASONS.iter().map(|r| {
+            let count = s.win_reasons.get(&reason_name(*r)).copied();
+            count.unwrap_or(0).to_string()
+        }));
+        row.extend(ACTIONS.iter().map(|a| {
+            let rate = s.executive_actions_per_game.get(*a).copied();
+            format!("{:.4}", rate.unwrap_or(0.0))
+        }));
+        println!("{}", row.join(","));
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let args = parse_args()?;
+    let stats = args
+        .players
+        .clone()
+        .map(|players| simulate(players, &args))
+        .collect::<Vec<_>>();
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&stats)?);
+    } else {
+        print_csv(&stats);
+    }
+    Ok(())
+}
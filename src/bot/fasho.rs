@@ -0,0 +1,120 @@
+use rand::{Rng, RngCore};
+
+use super::{least, most, pick_laws, suspicion, targets, LiberalStrategy, Strategy};
+use crate::{
+    ExecutiveActionResponse, ExecutiveActionTask, Faction, GameView, Law, PlayerId, Role, Task,
+    TaskAction,
+};
+
+/// Plays for the fascists: supports governments with fellow fascists, passes
+/// fascist laws, and tries to get Hitler elected once that wins the game.
+///
+/// Knowledge of the team is taken from the view, so a Hitler that does not
+/// know its team falls back to playing like a liberal, apart from the laws.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FashoStrategy;
+
+impl FashoStrategy {
+    fn team(view: &GameView) -> Vec<PlayerId> {
+        view.board
+            .players
+            .iter()
+            .filter(|p| p.role.is_some_and(|role| role != Role::Liberal))
+            .map(|p| p.id.clone())
+            .collect()
+    }
+
+    fn hitler(view: &GameView) -> Option<PlayerId> {
+        view.board
+            .players
+            .iter()
+            .find(|p| p.role == Some(Role::FashoHitler))
+            .map(|p| p.id.clone())
+    }
+}
+
+impl Strategy for FashoStrategy {
+    fn act(&self, view: &GameView, task: &Task, rng: &mut dyn RngCore) -> TaskAction {
+        let team = Self::team(view);
+        if team.len() <= 1 && !matches!(task, Task::PickLaws(..)) {
+            return LiberalStrategy.act(view, task, rng);
+        }
+
+        let hitler_wins = view.board.passed_fasho_laws >= view.rules.hitler_threshold;
+        let hitler = Self::hitler(view);
+        let scores = suspicion(view);
+        // Fellow fascists should look trustworthy, liberals should look suspicious.
+        let trust = |id: &PlayerId| {
+            if team.contains(id) {
+                -scores.get(id).copied().unwrap_or(0)
+            } else {
+                scores.get(id).copied().unwrap_or(0)
+            }
+        };
+
+        match task {
+            Task::ChooseChancellor(options) => {
+                let chancellor = match &hitler {
+                    Some(hitler) if hitler_wins && options.contains(hitler) => hitler.clone(),
+                    _ if rng.gen_bool(0.6) => least(
+                        options.iter().filter(|id| team.contains(id)),
+                        |id| -trust(id),
+                        rng,
+                    )
+                    .or_else(|| least(options, |id| -trust(id), rng))
+                    .unwrap(),
+                    _ => least(options, trust, rng).expect("no eligible chancellor"),
+                };
+                TaskAction::ChooseChancellor(chancellor)
+            }
+            Task::Vote(proposal) => {
+                let hitler_elected = hitler_wins && hitler.as_ref() == Some(&proposal.chancellor);
+                let with_team =
+                    team.contains(&proposal.president) || team.contains(&proposal.chancellor);
+                TaskAction::Vote(hitler_elected || with_team || rng.gen_bool(0.3))
+            }
+            Task::PickLaws(laws, can_veto) => {
+                if *can_veto && laws.iter().all(|law| *law == Law::Liberal) {
+                    TaskAction::Veto(true)
+                } else {
+                    pick_laws(laws, Faction::Fasho, rng)
+                }
+            }
+            Task::ConfirmVeto => TaskAction::Veto(false),
+            Task::ExecutiveAction(action) => {
+                let targets = targets(action);
+                let liberals = targets
+                    .iter()
+                    .filter(|id| !team.contains(id))
+                    .collect::<Vec<_>>();
+                let response = match action {
+                    ExecutiveActionTask::Kill(_) => {
+                        // The most trusted liberal is the most dangerous one.
+                        let target =
+                            least(liberals, |id| scores.get(id).copied().unwrap_or(0), rng)
+                                .or_else(|| most(&targets, trust, rng))
+                                .unwrap();
+                        ExecutiveActionResponse::Kill(target)
+                    }
+                    ExecutiveActionTask::RevealFaction(_) => {
+                        let target = least(liberals, trust, rng)
+                            .or_else(|| most(&targets, trust, rng))
+                            .unwrap();
+                        ExecutiveActionResponse::RevealFaction(target)
+                    }
+                    ExecutiveActionTask::DeterminePresident(_) => {
+                        let target =
+                            least(targets.iter().filter(|id| team.contains(id)), trust, rng)
+                                .or_else(|| least(&targets, trust, rng))
+                                .unwrap();
+                        ExecutiveActionResponse::DeterminePresident(target)
+                    }
+                    ExecutiveActionTask::RevealNextCards(_) => {
+                        ExecutiveActionResponse::RevealNextCards
+                    }
+                };
+                TaskAction::ExecuteAction(response)
+            }
+        }
+    }
+}
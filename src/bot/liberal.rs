@@ -0,0 +1,82 @@
+use rand::RngCore;
+
+use super::{least, most, pick_laws, suspicion, targets, Strategy};
+use crate::{
+    ExecutiveActionResponse, ExecutiveActionTask, Faction, GameView, Law, StateView, Task,
+    TaskAction,
+};
+
+/// Plays for the liberals: passes liberal laws and distrusts everyone whose
+/// governments passed fascist ones.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LiberalStrategy;
+
+/// Combined suspicion above which a liberal rejects a government.
+const TRUST_LIMIT: i32 = 2;
+
+impl Strategy for LiberalStrategy {
+    fn act(&self, view: &GameView, task: &Task, rng: &mut dyn RngCore) -> TaskAction {
+        let scores = suspicion(view);
+        let score = |id: &String| scores.get(id).copied().unwrap_or(0);
+
+        match task {
+            Task::ChooseChancellor(options) => {
+                let chancellor = least(options, score, rng).expect("no eligible chancellor");
+                TaskAction::ChooseChancellor(chancellor)
+            }
+            Task::Vote(proposal) => {
+                let risk = score(&proposal.president) + score(&proposal.chancellor);
+                // Avoid chaos, unless the government is known to be fascist.
+                let desperate = view.board.no_goverment_counter + 1
+                    == view.rules.election_tracker_limit
+                    && risk < 100;
+                TaskAction::Vote(risk <= TRUST_LIMIT || desperate)
+            }
+            Task::PickLaws(laws, can_veto) => {
+                if *can_veto && laws.iter().all(|law| *law == Law::Fasho) {
+                    TaskAction::Veto(true)
+                } else {
+                    pick_laws(laws, Faction::Liberal, rng)
+                }
+            }
+            Task::ConfirmVeto => {
+                let accept = match &view.state {
+                    StateView::AskVeto { chancellor, .. } => score(chancellor) <= TRUST_LIMIT,
+                    _ => false,
+                };
+                TaskAction::Veto(accept)
+            }
+            Task::ExecutiveAction(action) => {
+                let targets = targets(action);
+                let unknown = view
+                    .board
+                    .players
+                    .iter()
+                    .filter(|p| p.faction.is_none() && targets.contains(&p.id))
+                    .map(|p| &p.id)
+                    .collect::<Vec<_>>();
+
+                let response = match action {
+                    ExecutiveActionTask::Kill(_) => {
+                        ExecutiveActionResponse::Kill(most(&targets, score, rng).unwrap())
+                    }
+                    ExecutiveActionTask::RevealFaction(_) => {
+                        let target = most(unknown, score, rng)
+                            .or_else(|| most(&targets, score, rng))
+                            .unwrap();
+                        ExecutiveActionResponse::RevealFaction(target)
+                    }
+                    ExecutiveActionTask::DeterminePresident(_) => {
+                        ExecutiveActionResponse::DeterminePresident(
+                            least(&targets, score, rng).unwrap(),
+                        )
+                    }
+                    ExecutiveActionTask::RevealNextCards(_) => {
+                        ExecutiveActionResponse::RevealNextCards
+                    }
+                };
+                TaskAction::ExecuteAction(response)
+            }
+        }
+    }
+}
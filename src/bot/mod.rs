@@ -0,0 +1,161 @@
+//! Rule based players that fill empty seats.
+
+mod fasho;
+mod liberal;
+
+use std::collections::HashMap;
+
+pub use fasho::FashoStrategy;
+pub use liberal::LiberalStrategy;
+use rand::{seq::SliceRandom, Rng, RngCore};
+
+use crate::{
+    Event, ExecutiveActionTask, Faction, GameState, GameView, Law, PlayerId, Role, Task, TaskAction,
+};
+
+/// Decides which action a player takes for a task, based on what that
+/// player can see of the game.
+pub trait Strategy {
+    fn act(&self, view: &GameView, task: &Task, rng: &mut dyn RngCore) -> TaskAction;
+}
+
+/// The strategy a bot plays for its role.
+pub fn strategy_for(role: Role) -> Box<dyn Strategy> {
+    match role {
+        Role::Liberal => Box::new(LiberalStrategy),
+        Role::Fasho | Role::FashoHitler => Box::new(FashoStrategy),
+    }
+}
+
+/// The next action of every bot that currently holds a task.
+pub fn bot_actions(game: &GameState, rng: &mut dyn RngCore) -> Vec<(PlayerId, TaskAction)> {
+    game.players()
+        .iter()
+        .filter(|p| p.bot)
+        .filter_map(|p| {
+            let task = game.tasks(&p.id)?;
+            let view = game.view(&p.id)?;
+            let action = strategy_for(p.role).act(&view, &task, rng);
+            Some((p.id.clone(), action))
+        })
+        .collect()
+}
+
+/// Lets bots act until none of them holds a task anymore, so that the game
+/// waits for a human or is over. Returns the number of actions taken.
+pub fn run_bots(game: &mut GameState, rng: &mut dyn RngCore) -> usize {
+    let mut count = 0;
+    loop {
+        let actions = bot_actions(game, rng);
+        if actions.is_empty() {
+            return count;
+        }
+
+        let mut progress = false;
+        for (player, action) in actions {
+            match game.on_action(player.clone(), action) {
+                Ok(()) => {
+                    count += 1;
+                    progress = true;
+                }
+                Err(e) => tracing::error!("bot {player:?} made an invalid move: {e}"),
+            }
+        }
+        if !progress {
+            return count;
+        }
+    }
+}
+
+/// How suspicious each player looks based on public information: the laws
+/// their governments passed, the governments they voted for, and revealed
+/// factions. Higher is more likely to be a fascist.
+pub(crate) fn suspicion(view: &GameView) -> HashMap<PlayerId, i32> {
+    let board = &view.board;
+    let mut scores = board
+        .players
+        .iter()
+        .map(|p| (p.id.clone(), 0))
+        .collect::<HashMap<_, _>>();
+
+    let mut supporters = Vec::new();
+    for event in &board.history {
+        match event {
+            Event::Vote { votes, success, .. } => {
+                supporters = votes
+                    .iter()
+                    .filter(|(_, ja)| **ja && *success)
+                    .map(|(id, _)| id.clone())
+                    .collect();
+            }
+            Event::PlayedLaw {
+                president,
+                chancellor: Some(chancellor),
+                law,
+            } => {
+                let (government, support) = match law {
+                    Law::Fasho => (2, 1),
+                    Law::Liberal => (-1, 0),
+                };
+                *scores.entry(president.clone()).or_default() += government;
+                *scores.entry(chancellor.clone()).or_default() += government;
+                for id in supporters.drain(..) {
+                    *scores.entry(id).or_default() += support;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for player in &board.players {
+        match player.faction {
+            Some(Faction::Fasho) => *scores.entry(player.id.clone()).or_default() += 100,
+            Some(Faction::Liberal) => *scores.entry(player.id.clone()).or_default() -= 100,
+            None => {}
+        }
+    }
+    if let Some(me) = &view.me {
+        scores.remove(&me.id);
+    }
+    scores
+}
+
+/// The player among `options` that scores lowest, ties broken randomly.
+pub(crate) fn least<'a>(
+    options: impl IntoIterator<Item = &'a PlayerId>,
+    score: impl Fn(&PlayerId) -> i32,
+    rng: &mut dyn RngCore,
+) -> Option<PlayerId> {
+    let mut options = options.into_iter().collect::<Vec<_>>();
+    options.shuffle(rng);
+    options.into_iter().min_by_key(|id| score(id)).cloned()
+}
+
+pub(crate) fn most<'a>(
+    options: impl IntoIterator<Item = &'a PlayerId>,
+    score: impl Fn(&PlayerId) -> i32,
+    rng: &mut dyn RngCore,
+) -> Option<PlayerId> {
+    least(options, |id| -score(id), rng)
+}
+
+/// The players an executive action may be used on.
+pub(crate) fn targets(action: &ExecutiveActionTask) -> Vec<PlayerId> {
+    match action {
+        ExecutiveActionTask::Kill(targets)
+        | ExecutiveActionTask::RevealFaction(targets)
+        | ExecutiveActionTask::DeterminePresident(targets) => targets.clone(),
+        ExecutiveActionTask::RevealNextCards(_) => Vec::new(),
+    }
+}
+
+/// Keeps the laws of `faction` if possible, discarding another one.
+pub(crate) fn pick_laws(laws: &[Law], prefer: Faction, rng: &mut dyn RngCore) -> TaskAction {
+    let mut keep = laws.to_vec();
+    let idx = keep
+        .iter()
+        .position(|law| *law != prefer)
+        .unwrap_or_else(|| rng.gen_range(0..keep.len()));
+    let discard = keep.remove(idx);
+    TaskAction::PickedLaws(keep, discard)
+}
@@ -1,16 +1,166 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+use super::{deck::HAND_SIZE, error::RulesError, types::ExecutiveAction};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Config {
     pub id: String,
     pub no_votes: bool,
+    /// Seed of the game's RNG. Chosen randomly on game creation if not set.
+    pub seed: Option<u64>,
+    /// Whether spectators may watch with all roles revealed while the game
+    /// runs. Requires a [`Config::spectator_delay`].
+    pub omniscient_spectators: bool,
+    /// Seconds by which updates to omniscient spectators are delayed.
+    pub spectator_delay: u64,
+    pub timeouts: Timeouts,
+    /// Lets whoever knows it claim the host role. Without a password, the
+    /// first player to join hosts the game.
+    pub host_password: Option<String>,
+    pub rules: Rules,
+    pub chat: ChatRules,
+}
+
+impl Config {
+    /// Reads the config of a new game from a request body. An empty body
+    /// stands for the default config, anything else has to be valid.
+    pub fn from_body(body: &[u8]) -> serde_json::Result<Self> {
+        if body.iter().all(u8::is_ascii_whitespace) {
+            return Ok(Config::default());
+        }
+        serde_json::from_slice(body)
+    }
+}
+
+/// The rules a game is played by. Defaults to the official rules.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// Liberal laws in the policy deck.
+    pub liberal_laws: usize,
+    /// Fascist laws in the policy deck.
+    pub fasho_laws: usize,
+    /// Liberal laws that win the game for the liberals.
+    pub liberal_track: usize,
+    /// Fascist laws that win the game for the fascists.
+    pub fasho_track: usize,
+    /// Fascist laws after which electing Hitler as chancellor wins the game.
+    pub hitler_threshold: usize,
+    /// Fascist laws after which the chancellor may ask for a veto.
+    pub veto_threshold: usize,
+    /// Failed elections in a row after which the top law is played.
+    pub election_tracker_limit: usize,
+    /// The executive action of each slot on the fascist track. `None` uses
+    /// the official track for the number of players.
+    pub executive_track: Option<Vec<Option<ExecutiveAction>>>,
+    /// With this many players alive or fewer, only the last chancellor is
+    /// barred from being nominated, not the last president.
+    pub small_game_size: usize,
 }
 
-impl Default for Config {
+impl Default for Rules {
     fn default() -> Self {
-        Self {
-            id: String::new(),
-            no_votes: false,
+        Rules {
+            liberal_laws: 6,
+            fasho_laws: 11,
+            liberal_track: 5,
+            fasho_track: 6,
+            hitler_threshold: 3,
+            veto_threshold: 5,
+            election_tracker_limit: 3,
+            executive_track: None,
+            small_game_size: 5,
         }
     }
 }
+
+impl Rules {
+    /// Checks that a game can be played, and won, by these rules.
+    pub fn validate(&self) -> Result<(), RulesError> {
+        if self.liberal_track == 0 || self.fasho_track == 0 {
+            return Err(RulesError::EmptyTrack);
+        }
+        // Even when both tracks are one law short of winning, a full hand
+        // has to remain in the deck.
+        let passable = (self.liberal_track - 1) + (self.fasho_track - 1);
+        if self.liberal_laws < self.liberal_track
+            || self.fasho_laws < self.fasho_track
+            || self.liberal_laws + self.fasho_laws < passable + HAND_SIZE
+        {
+            return Err(RulesError::DeckTooSmall);
+        }
+        if self.hitler_threshold > self.fasho_track {
+            return Err(RulesError::HitlerThreshold(self.hitler_threshold));
+        }
+        if self.veto_threshold > self.fasho_track {
+            return Err(RulesError::VetoThreshold(self.veto_threshold));
+        }
+        if self.election_tracker_limit == 0 {
+            return Err(RulesError::ElectionTrackerLimit);
+        }
+        if let Some(track) = &self.executive_track {
+            if track.len() != self.fasho_track {
+                return Err(RulesError::ExecutiveTrackLength {
+                    expected: self.fasho_track,
+                    actual: track.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// The executive actions on the fascist track for a game of `players`,
+    /// or `None` if there is no official track for that many.
+    pub fn executive_track_for(&self, players: usize) -> Option<Vec<Option<ExecutiveAction>>> {
+        match &self.executive_track {
+            Some(track) => Some(track.clone()),
+            None => {
+                let mut track = ExecutiveAction::for_player_count(players)?.to_vec();
+                track.resize(self.fasho_track, None);
+                Some(track)
+            }
+        }
+    }
+}
+
+/// Restrictions on the chat while a round is running. Outside of rounds,
+/// everyone may talk to everyone.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChatRules {
+    /// The president and chancellor may not talk during the legislative session.
+    pub mute_legislative: bool,
+    /// Dead players may only talk to each other, in their own channel.
+    pub dead_channel: bool,
+    /// The fascists who know each other get a private channel.
+    pub fasho_channel: bool,
+}
+
+impl Default for ChatRules {
+    fn default() -> Self {
+        ChatRules {
+            mute_legislative: true,
+            dead_channel: true,
+            fasho_channel: false,
+        }
+    }
+}
+
+/// Time limits per phase in seconds. Once a limit is exceeded, the pending
+/// players' tasks are resolved with a default action. `None` disables the limit.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Timeouts {
+    /// The president picks a random eligible chancellor.
+    pub choose_chancellor: Option<u64>,
+    /// Pending voters vote `default_vote`.
+    pub vote: Option<u64>,
+    /// The president or chancellor discards a random law, and
+    /// pending vetos are rejected.
+    pub legislative: Option<u64>,
+    /// The president executes the action on a random target.
+    pub executive: Option<u64>,
+    /// The vote cast for players that did not vote in time. Defaults to "nein".
+    #[serde(default)]
+    pub default_vote: bool,
+}
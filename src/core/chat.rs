@@ -0,0 +1,168 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::{
+    error::ActionError,
+    types::{PlayerId, Role},
+    view::Viewer,
+    DynamicState, GameState,
+};
+
+/// The maximum length of a chat message in characters.
+pub const MAX_CHAT_LENGTH: usize = 500;
+/// The number of chat messages kept per game. Older ones are dropped.
+const CHAT_HISTORY: usize = 500;
+
+/// Who a chat message is meant for.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ChatChannel {
+    /// Everyone at the table, and spectators.
+    #[default]
+    All,
+    /// Dead players, see [`ChatRules::dead_channel`].
+    Dead,
+    /// The fascist team, see [`ChatRules::fasho_channel`].
+    Fasho,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ChatMessage {
+    pub id: u64,
+    pub author: PlayerId,
+    pub channel: ChatChannel,
+    pub text: String,
+    /// When the message was sent, in milliseconds since the Unix epoch.
+    pub time: u64,
+}
+
+/// A chat message together with the players who may read it, fixed when it
+/// was sent. `None` means everyone, including spectators.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(super) struct StoredChat {
+    message: ChatMessage,
+    audience: Option<Vec<PlayerId>>,
+}
+
+impl GameState {
+    /// Posts a chat message of `author`, if the chat rules of the game allow it.
+    #[instrument(name = "game", fields(id = %self.cfg.id), skip(self, text))]
+    pub fn on_chat(
+        &mut self,
+        author: PlayerId,
+        channel: ChatChannel,
+        text: String,
+    ) -> Result<(), ActionError> {
+        let text = text.trim();
+        if text.is_empty() || text.chars().count() > MAX_CHAT_LENGTH {
+            return Err(ActionError::InvalidMessage);
+        }
+        let audience = self.chat_audience(&author, channel)?;
+
+        let time = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |t| t.as_millis() as u64);
+        let message = ChatMessage {
+            id: self.chat.last().map_or(0, |c| c.message.id + 1),
+            author,
+            channel,
+            text: text.to_string(),
+            time,
+        };
+        if self.chat.len() == CHAT_HISTORY {
+            self.chat.remove(0);
+        }
+        self.chat.push(StoredChat { message, audience });
+        self.changed();
+        Ok(())
+    }
+
+    /// The readers of a message `author` posts in `channel`.
+    fn chat_audience(
+        &self,
+        author: &PlayerId,
+        channel: ChatChannel,
+    ) -> Result<Option<Vec<PlayerId>>, ActionError> {
+        let player = self
+            .board
+            .players
+            .iter()
+            .find(|p| &p.id == author)
+            .ok_or(ActionError::NotAPlayer)?;
+        let rules = &self.cfg.chat;
+        if !self.is_running() {
+            return match channel {
+                ChatChannel::All => Ok(None),
+                _ => Err(ActionError::InvalidChannel(channel)),
+            };
+        }
+
+        match channel {
+            ChatChannel::All => {
+                if !player.alive && rules.dead_channel {
+                    return Err(ActionError::Muted);
+                }
+                if rules.mute_legislative && self.in_government(author) {
+                    return Err(ActionError::Muted);
+                }
+                Ok(None)
+            }
+            ChatChannel::Dead if rules.dead_channel && !player.alive => Ok(Some(
+                self.board
+                    .players
+                    .iter()
+                    .filter(|p| !p.alive)
+                    .map(|p| p.id.clone())
+                    .collect(),
+            )),
+            ChatChannel::Fasho if rules.fasho_channel => {
+                let team = self.fasho_team();
+                if !team.contains(author) {
+                    return Err(ActionError::InvalidChannel(channel));
+                }
+                Ok(Some(team))
+            }
+            _ => Err(ActionError::InvalidChannel(channel)),
+        }
+    }
+
+    /// Whether `player` is president or chancellor during the legislative session.
+    fn in_government(&self, player: &PlayerId) -> bool {
+        match &self.state {
+            DynamicState::PresidentChooseLaws { chancellor, .. }
+            | DynamicState::ChancellorChooseLaws { chancellor, .. }
+            | DynamicState::AskVeto { chancellor, .. } => {
+                player == chancellor || *player == self.board.current_president
+            }
+            _ => false,
+        }
+    }
+
+    /// The fascists who know each other. Hitler only knows the team in
+    /// games of fewer than seven players.
+    fn fasho_team(&self) -> Vec<PlayerId> {
+        let small = self.board.players.len() < 7;
+        self.board
+            .players
+            .iter()
+            .filter(|p| p.role == Role::Fasho || (p.role == Role::FashoHitler && small))
+            .map(|p| p.id.clone())
+            .collect()
+    }
+
+    /// The chat messages `viewer` may read, that were sent after the message
+    /// with id `after`.
+    pub fn chat_for(&self, viewer: &Viewer, after: Option<u64>) -> Vec<ChatMessage> {
+        self.chat
+            .iter()
+            .filter(|c| after.is_none_or(|after| c.message.id > after))
+            .filter(|c| match (&c.audience, viewer) {
+                (None, _) => true,
+                (Some(audience), Viewer::Player(id)) => audience.contains(id),
+                (Some(_), Viewer::Spectator(_)) => false,
+            })
+            .map(|c| c.message.clone())
+            .collect()
+    }
+}
@@ -0,0 +1,64 @@
+use std::iter::repeat_n;
+
+use rand::prelude::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use super::types::Law;
+
+/// Laws dealt to the president in a legislative session.
+pub const HAND_SIZE: usize = 3;
+
+/// The policy deck: the draw pile, whose front is the top of the deck, and
+/// the discarded laws. Passed laws leave the deck for good.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Deck {
+    pub(super) draw_pile: Vec<Law>,
+    pub(super) discard_pile: Vec<Law>,
+}
+
+impl Deck {
+    pub fn new(liberals: usize, fashos: usize, rng: &mut impl Rng) -> Deck {
+        let mut draw_pile = repeat_n(Law::Liberal, liberals)
+            .chain(repeat_n(Law::Fasho, fashos))
+            .collect::<Vec<_>>();
+        draw_pile.shuffle(rng);
+        Deck {
+            draw_pile,
+            discard_pile: Vec::new(),
+        }
+    }
+
+    pub fn draw_pile(&self) -> usize {
+        self.draw_pile.len()
+    }
+
+    pub fn discard_pile(&self) -> usize {
+        self.discard_pile.len()
+    }
+
+    /// Takes the top `N` laws. The deck is reshuffled at the end of every
+    /// legislative session, so a draw never runs short in a valid game.
+    pub fn draw<const N: usize>(&mut self) -> [Law; N] {
+        let laws = self.draw_pile.drain(..N).collect::<Vec<_>>();
+        laws.try_into().expect("drained exactly N laws")
+    }
+
+    /// The top laws of the deck, without drawing them.
+    pub fn peek(&self) -> Vec<Law> {
+        self.draw_pile[..HAND_SIZE.min(self.draw_pile.len())].to_vec()
+    }
+
+    pub fn discard(&mut self, laws: &[Law]) {
+        self.discard_pile.extend_from_slice(laws);
+    }
+
+    /// Ends a legislative session: if fewer laws than a full hand remain,
+    /// the discarded laws are shuffled back into the deck.
+    pub fn end_session(&mut self, rng: &mut impl Rng) {
+        if self.draw_pile.len() < HAND_SIZE {
+            self.draw_pile.append(&mut self.discard_pile);
+            self.draw_pile.shuffle(rng);
+        }
+    }
+}
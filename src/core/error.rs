@@ -0,0 +1,134 @@
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    chat::{ChatChannel, MAX_CHAT_LENGTH},
+    types::PlayerId,
+    MAX_NAME_LENGTH,
+};
+
+/// Reasons why [`GameState::on_action`](super::GameState::on_action) rejected an action.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum ActionError {
+    NotAPlayer,
+    Dead,
+    NotYourTurn,
+    WrongPhase,
+    InvalidTarget(PlayerId),
+    IneligibleChancellor(PlayerId),
+    BadLawSelection,
+    VetoLocked,
+    NotHost,
+    WrongPassword,
+    InvalidSeat(usize),
+    Paused,
+    /// Display names have to be unique within a game, ignoring case.
+    NameTaken(String),
+    InvalidName,
+    UnknownAccessKey,
+    /// The chat rules forbid the player to talk right now.
+    Muted,
+    InvalidChannel(ChatChannel),
+    InvalidMessage,
+    CannotStart(StartError),
+    InvalidRules(RulesError),
+}
+
+/// Reasons why a game cannot be started.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum StartError {
+    TooFewPlayers { min: usize, actual: usize },
+    TooManyPlayers { max: usize, actual: usize },
+    PlayersDisconnected(Vec<PlayerId>),
+    AlreadyRunning,
+}
+
+impl fmt::Display for ActionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotAPlayer => write!(f, "you are not a player in this game"),
+            Self::Dead => write!(f, "dead players cannot act"),
+            Self::NotYourTurn => write!(f, "it is not your turn"),
+            Self::WrongPhase => write!(f, "action is not allowed in the current phase"),
+            Self::InvalidTarget(id) => write!(f, "{id:?} is not a valid target"),
+            Self::IneligibleChancellor(id) => write!(f, "{id:?} is not eligible as chancellor"),
+            Self::BadLawSelection => write!(f, "selected laws do not match the dealt laws"),
+            Self::VetoLocked => write!(f, "veto power is not available"),
+            Self::NotHost => write!(f, "only the host may do this"),
+            Self::WrongPassword => write!(f, "wrong host password"),
+            Self::InvalidSeat(seat) => write!(f, "there is no seat {seat}"),
+            Self::Paused => write!(f, "the game is paused"),
+            Self::NameTaken(name) => write!(f, "the name {name:?} is already taken"),
+            Self::InvalidName => write!(
+                f,
+                "names must have between 1 and {MAX_NAME_LENGTH} characters"
+            ),
+            Self::UnknownAccessKey => write!(f, "no player has this access key"),
+            Self::Muted => write!(f, "you may not talk right now"),
+            Self::InvalidChannel(channel) => write!(f, "you may not talk in {channel:?}"),
+            Self::InvalidMessage => write!(
+                f,
+                "messages must have between 1 and {MAX_CHAT_LENGTH} characters"
+            ),
+            Self::CannotStart(e) => write!(f, "cannot start the game: {e}"),
+            Self::InvalidRules(e) => write!(f, "invalid rules: {e}"),
+        }
+    }
+}
+
+impl Error for ActionError {}
+
+impl fmt::Display for StartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooFewPlayers { min, actual } => {
+                write!(
+                    f,
+                    "at least {min} players are needed, but only {actual} joined"
+                )
+            }
+            Self::TooManyPlayers { max, actual } => {
+                write!(f, "at most {max} players can play, but {actual} joined")
+            }
+            Self::PlayersDisconnected(ids) => write!(f, "{ids:?} are not connected"),
+            Self::AlreadyRunning => write!(f, "the game is already running"),
+        }
+    }
+}
+
+impl Error for StartError {}
+
+/// Reasons why [`Rules`](super::Rules) are not playable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum RulesError {
+    EmptyTrack,
+    DeckTooSmall,
+    HitlerThreshold(usize),
+    VetoThreshold(usize),
+    ElectionTrackerLimit,
+    ExecutiveTrackLength { expected: usize, actual: usize },
+}
+
+impl fmt::Display for RulesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyTrack => write!(f, "both factions need at least one law to win"),
+            Self::DeckTooSmall => write!(f, "the deck cannot fill the law tracks"),
+            Self::HitlerThreshold(n) => {
+                write!(f, "Hitler threshold {n} lies beyond the fascist track")
+            }
+            Self::VetoThreshold(n) => write!(f, "veto threshold {n} lies beyond the fascist track"),
+            Self::ElectionTrackerLimit => write!(f, "the election tracker limit must be positive"),
+            Self::ExecutiveTrackLength { expected, actual } => write!(
+                f,
+                "the executive track has {actual} slots, but the fascist track {expected}"
+            ),
+        }
+    }
+}
+
+impl Error for RulesError {}
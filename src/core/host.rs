@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+use super::{
+    cfg::Config,
+    error::ActionError,
+    log::LogEntry,
+    types::{Player, PlayerId},
+    DynamicState, GameState,
+};
+
+/// Lobby controls reserved to the host of a game.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum HostAction {
+    /// Removes a player from the lobby.
+    Kick(PlayerId),
+    /// Moves a player to another seat, shifting the players in between.
+    MoveSeat {
+        player: PlayerId,
+        seat: usize,
+    },
+    TransferHost(PlayerId),
+    /// Freezes a running game: nobody can act and no timer runs out.
+    Pause,
+    /// Continues a paused game. The current phase's clock starts over.
+    Resume,
+    /// Ends a running game without a winner and returns to the lobby.
+    Abort,
+    /// Replaces the config of a game that has not started yet. The id and
+    /// seed of the game are kept.
+    EditConfig(Box<Config>),
+}
+
+impl GameState {
+    /// The player who controls the lobby, see [`HostAction`].
+    pub fn host(&self) -> Option<&Player> {
+        let host = self.board.host.as_ref()?;
+        self.board.players.iter().find(|p| &p.id == host)
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.board.paused
+    }
+
+    /// Hands the seat of the host to the first human, or to a bot if no
+    /// human is seated, unless a human already holds it.
+    pub(super) fn assign_host(&mut self) {
+        if self.host().is_some_and(|host| !host.bot) {
+            return;
+        }
+        let host = self
+            .board
+            .players
+            .iter()
+            .find(|p| !p.bot)
+            .or(self.board.players.first());
+        self.board.host = host.map(|p| p.id.clone());
+    }
+
+    /// Makes `player` the host, if `password` matches the configured host password.
+    #[instrument(name = "game", fields(id = %self.cfg.id), skip(self, password))]
+    pub fn claim_host(&mut self, player: &PlayerId, password: &str) -> Result<(), ActionError> {
+        if self.board.index(player).is_none() {
+            return Err(ActionError::NotAPlayer);
+        }
+        if self.cfg.host_password.as_deref() != Some(password) {
+            return Err(ActionError::WrongPassword);
+        }
+
+        tracing::info!("{player:?} claimed host");
+        self.make_host(player);
+        Ok(())
+    }
+
+    /// Records that `player` claimed the host role and hands it to them.
+    pub(super) fn make_host(&mut self, player: &PlayerId) {
+        self.log.entries.push(LogEntry::ClaimedHost(player.clone()));
+        self.board.host = Some(player.clone());
+        self.changed();
+    }
+
+    /// Applies a lobby control of `player` and records it in the action log,
+    /// if it was accepted.
+    #[instrument(name = "game", fields(id = %self.cfg.id), skip(self))]
+    pub fn on_host_action(
+        &mut self,
+        player: PlayerId,
+        action: HostAction,
+    ) -> Result<(), ActionError> {
+        self.seen(&player);
+        self.apply_host_action(&player, action.clone())?;
+        self.log.entries.push(LogEntry::Host { player, action });
+        self.changed();
+        Ok(())
+    }
+
+    fn apply_host_action(
+        &mut self,
+        player: &PlayerId,
+        action: HostAction,
+    ) -> Result<(), ActionError> {
+        self.authorize_host(player)?;
+
+        match action {
+            HostAction::Kick(target) => {
+                if self.is_running() {
+                    return Err(ActionError::WrongPhase);
+                }
+                let Some(index) = self.board.index(&target).filter(|_| target != *player) else {
+                    return Err(ActionError::InvalidTarget(target));
+                };
+                tracing::info!("{target:?} was kicked");
+                self.board.unseat(index);
+                // Their connection is told that they were kicked and closed.
+                self.sessions.remove(&target);
+            }
+            HostAction::MoveSeat { player, seat } => {
+                if self.is_running() {
+                    return Err(ActionError::WrongPhase);
+                }
+                let Some(index) = self.board.index(&player) else {
+                    return Err(ActionError::InvalidTarget(player));
+                };
+                if seat >= self.board.players.len() {
+                    return Err(ActionError::InvalidSeat(seat));
+                }
+                let moved = self.board.players.remove(index);
+                self.board.players.insert(seat, moved);
+            }
+            HostAction::TransferHost(target) => {
+                if !self.board.players.iter().any(|p| p.id == target && !p.bot) {
+                    return Err(ActionError::InvalidTarget(target));
+                }
+                self.board.host = Some(target);
+            }
+            HostAction::Pause => {
+                if !self.is_running() || self.board.paused {
+                    return Err(ActionError::WrongPhase);
+                }
+                self.board.paused = true;
+                self.deadline = None;
+            }
+            HostAction::Resume => {
+                if !self.board.paused {
+                    return Err(ActionError::WrongPhase);
+                }
+                self.board.paused = false;
+                self.reset_deadline();
+            }
+            HostAction::Abort => {
+                if !self.is_running() {
+                    return Err(ActionError::WrongPhase);
+                }
+                tracing::info!("game aborted");
+                self.state = DynamicState::Uninit;
+                self.board.reset();
+                self.deadline = None;
+            }
+            HostAction::EditConfig(cfg) => {
+                if self.is_running() {
+                    return Err(ActionError::WrongPhase);
+                }
+                cfg.rules.validate().map_err(ActionError::InvalidRules)?;
+                self.log.config.get_or_insert_with(|| self.cfg.clone());
+                self.cfg = Config {
+                    id: self.cfg.id.clone(),
+                    seed: self.cfg.seed,
+                    ..*cfg
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `credential` is the host password, or the access key of
+    /// the host. Used by requests that do not belong to a player's session.
+    pub fn authorize_host_credential(&self, credential: &str) -> Result<(), ActionError> {
+        if credential.is_empty() {
+            return Err(ActionError::WrongPassword);
+        }
+        if self.cfg.host_password.as_deref() == Some(credential) {
+            return Ok(());
+        }
+        let player = self
+            .board
+            .players
+            .iter()
+            .find(|p| p.access_key == credential)
+            .ok_or(ActionError::WrongPassword)?;
+        self.authorize_host(&player.id)
+    }
+
+    pub(crate) fn authorize_host(&self, player: &PlayerId) -> Result<(), ActionError> {
+        if self.board.index(player).is_none() {
+            return Err(ActionError::NotAPlayer);
+        }
+        if self.board.host.as_ref() != Some(player) {
+            return Err(ActionError::NotHost);
+        }
+        Ok(())
+    }
+}
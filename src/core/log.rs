@@ -0,0 +1,166 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    cfg::Config,
+    error::ActionError,
+    host::HostAction,
+    types::{Player, PlayerId, TaskAction, User},
+    GameState,
+};
+
+/// Append-only record of everything that changed a game, together with the
+/// seed of the game's RNG. Replaying the entries on a fresh game with the same
+/// seed and config yields the same game again.
+///
+/// Each round additionally records its own seed, so that a finished round
+/// can be replayed without the seed of the game.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionLog {
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// The config the game was created with, if the host has edited it since.
+    #[serde(default)]
+    pub config: Option<Config>,
+    pub entries: Vec<LogEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum LogEntry {
+    Joined(Player),
+    Rejoined(PlayerId),
+    Left(PlayerId),
+    Action {
+        player: PlayerId,
+        action: TaskAction,
+    },
+    Host {
+        player: PlayerId,
+        action: HostAction,
+    },
+    ClaimedHost(PlayerId),
+    EditedUser {
+        player: PlayerId,
+        user: User,
+    },
+    /// The seed of the round that is started next.
+    Seeded(u64),
+}
+
+impl ActionLog {
+    pub fn new(seed: u64) -> ActionLog {
+        ActionLog {
+            seed: Some(seed),
+            config: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// A copy of the log without the players' access keys, the host
+    /// password and the seed of the game, fit to be shared. The seeds of
+    /// the rounds reveal all roles and are only kept if `with_round_seeds`.
+    pub fn redacted(&self, with_round_seeds: bool) -> ActionLog {
+        let mut log = self.clone();
+        log.seed = None;
+        if let Some(cfg) = &mut log.config {
+            cfg.host_password = None;
+            cfg.seed = None;
+        }
+        if !with_round_seeds {
+            log.entries
+                .retain(|entry| !matches!(entry, LogEntry::Seeded(_)));
+        }
+        for entry in &mut log.entries {
+            match entry {
+                LogEntry::Joined(player) => player.access_key.clear(),
+                LogEntry::Host {
+                    action: HostAction::EditConfig(cfg),
+                    ..
+                } => {
+                    cfg.host_password = None;
+                    cfg.seed = None;
+                }
+                _ => {}
+            }
+        }
+        log
+    }
+}
+
+/// Rebuilds a game step-by-step from an [`ActionLog`].
+#[derive(Debug)]
+pub struct Replay {
+    game: GameState,
+    entries: std::vec::IntoIter<LogEntry>,
+    step: usize,
+}
+
+impl Replay {
+    /// Prepares to replay `log` on a game with `cfg`, unless the log
+    /// records the config the game was created with.
+    pub fn new(cfg: Config, log: ActionLog) -> Replay {
+        let cfg = log.config.clone().unwrap_or(cfg);
+        Replay {
+            game: GameState::new(Config {
+                seed: log.seed,
+                ..cfg
+            }),
+            entries: log.entries.into_iter(),
+            step: 0,
+        }
+    }
+
+    /// The game as of the last applied entry.
+    pub fn game(&self) -> &GameState {
+        &self.game
+    }
+
+    /// The number of entries applied so far.
+    pub fn position(&self) -> usize {
+        self.step
+    }
+
+    /// Applies the next entry of the log. Returns `None` once the log is exhausted.
+    pub fn step(&mut self) -> Option<Result<LogEntry, ActionError>> {
+        let entry = self.entries.next()?;
+        self.step += 1;
+
+        let result = match entry.clone() {
+            LogEntry::Joined(player) => {
+                self.game.seat(player);
+                Ok(())
+            }
+            LogEntry::Rejoined(player) => {
+                self.game.rejoin(&player);
+                Ok(())
+            }
+            LogEntry::Left(player) => {
+                self.game.remove_player(&player);
+                Ok(())
+            }
+            LogEntry::Action { player, action } => self.game.on_action(player, action),
+            LogEntry::Host { player, action } => self.game.on_host_action(player, action),
+            LogEntry::ClaimedHost(player) => {
+                self.game.make_host(&player);
+                Ok(())
+            }
+            LogEntry::EditedUser { player, user } => self.game.edit_user(player, user),
+            LogEntry::Seeded(seed) => {
+                self.game.next_seed = Some(seed);
+                Ok(())
+            }
+        };
+        Some(result.map(|()| entry))
+    }
+
+    /// Applies all remaining entries, stopping at the first rejected one.
+    pub fn finish(mut self) -> Result<GameState, ActionError> {
+        while let Some(result) = self.step() {
+            if let Err(e) = result {
+                tracing::warn!("replay diverged at step {}: {e}", self.step);
+                return Err(e);
+            }
+        }
+        Ok(self.game)
+    }
+}
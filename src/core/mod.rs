@@ -1,37 +1,76 @@
 mod cfg;
+mod chat;
+mod deck;
+mod error;
+mod host;
+mod log;
+mod session;
+mod timer;
 mod types;
 mod view;
 
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::SystemTime;
 
-use cfg::Config;
-use rand::prelude::SliceRandom;
-use rand::random;
-use rand::thread_rng;
+use rand::rngs::StdRng;
+use rand::{random, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
 
-use tokio::sync::Notify;
+pub use cfg::*;
+pub use chat::*;
+pub use deck::*;
+pub use error::*;
+pub use host::*;
+pub use log::*;
+pub use session::*;
+use tokio::sync::watch;
 use tracing::instrument;
 pub use types::*;
 pub use view::*;
 
+/// The number of players the game supports.
+pub const MIN_PLAYERS: usize = 5;
+pub const MAX_PLAYERS: usize = 10;
+/// The maximum length of a display name in characters.
+pub const MAX_NAME_LENGTH: usize = 24;
+
 #[derive(Debug, Clone)]
 pub struct GameState {
     cfg: Config,
     board: BoardState,
     state: DynamicState,
-    notify: Arc<Notify>,
+    updates: Arc<watch::Sender<u64>>,
+    /// Draws the seed of each round, see [`GameState::start`].
+    seeds: StdRng,
+    /// The seed the next round must use, when replaying a log.
+    next_seed: Option<u64>,
+    rng: StdRng,
+    log: ActionLog,
+    deadline: Option<SystemTime>,
+    sessions: HashMap<PlayerId, PlayerSession>,
+    chat: Vec<StoredChat>,
+}
+
+/// Everything needed to restore a [`GameState`], e.g. after a server restart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameSnapshot {
+    cfg: Config,
+    board: BoardState,
+    state: DynamicState,
+    log: ActionLog,
+    #[serde(default)]
+    chat: Vec<StoredChat>,
 }
 
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 struct BoardState {
     players: Vec<Player>,
 
-    draw_pile: Vec<Law>,
-    discard_pile: Vec<Law>,
+    #[serde(flatten)]
+    deck: Deck,
 
-    executive_actions: [Option<ExecutiveAction>; 6],
+    executive_actions: Vec<Option<ExecutiveAction>>,
     voting_result: Option<HashMap<PlayerId, bool>>,
 
     passed_fasho_laws: usize,
@@ -43,10 +82,20 @@ struct BoardState {
     previous_chancellor: Option<PlayerId>,
 
     current_president: PlayerId,
-    next_president_by_rules: Option<PlayerId>,
+    /// The president in regular seat order. Differs from the current
+    /// president only during a special election.
+    #[serde(default)]
+    rotation: PlayerId,
 
     history: Vec<Event>,
-    revealed_factions: Option<(PlayerId, PlayerId, Faction)>,
+    /// Every investigation so far, as president, target and the target's faction.
+    #[serde(default)]
+    investigations: Vec<(PlayerId, PlayerId, Faction)>,
+
+    #[serde(default)]
+    host: Option<PlayerId>,
+    #[serde(default)]
+    paused: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -76,73 +125,301 @@ pub enum DynamicState {
         chancellor: PlayerId,
         laws: [Law; 2],
     },
+    GameOver {
+        winner: Win,
+        reason: WinReason,
+    },
 }
 
 impl GameState {
-    pub fn new(users: Vec<User>) -> GameState {
+    /// Creates a new game. The seeds of its rounds are drawn from an RNG
+    /// seeded from `cfg.seed`, or randomly if no seed is configured.
+    pub fn new(mut cfg: Config) -> GameState {
+        let seed = *cfg.seed.get_or_insert_with(random);
         GameState {
-            cfg: Config {
-                id: "001".to_string(),
-                no_votes: false,
-            },
-            board: BoardState::new(users),
+            cfg,
+            board: BoardState::new(Vec::new()),
             state: DynamicState::Uninit,
-            notify: Arc::new(Notify::new()),
+            updates: Arc::new(watch::channel(0).0),
+            seeds: StdRng::seed_from_u64(seed),
+            next_seed: None,
+            rng: StdRng::seed_from_u64(seed),
+            log: ActionLog::new(seed),
+            deadline: None,
+            sessions: HashMap::new(),
+            chat: Vec::new(),
+        }
+    }
+
+    /// Rebuilds a game by replaying its action log.
+    pub fn replay(cfg: Config, log: ActionLog) -> Result<GameState, ActionError> {
+        Replay::new(cfg, log).finish()
+    }
+
+    /// Restores a game from a snapshot. All players are considered
+    /// disconnected until they reconnect with their access key.
+    ///
+    /// The game is rebuilt from the snapshot's log, so that the RNG
+    /// continues where it left off. Should the replay not reproduce the
+    /// snapshot, the snapshot takes precedence.
+    pub fn restore(snapshot: GameSnapshot) -> GameState {
+        let mut game = match GameState::replay(snapshot.cfg.clone(), snapshot.log.clone()) {
+            Ok(game) => game,
+            Err(e) => {
+                tracing::error!("failed to replay game {:?}: {e}", snapshot.cfg.id);
+                GameState::new(Config {
+                    seed: snapshot.log.seed,
+                    ..snapshot.cfg.clone()
+                })
+            }
+        };
+        for player in &mut game.board.players {
+            player.connected = player.bot;
+        }
+
+        let mut board = snapshot.board;
+        for player in &mut board.players {
+            player.connected = player.bot;
+        }
+        if game.board != board || game.state != snapshot.state || game.log != snapshot.log {
+            tracing::warn!(
+                "replay of game {:?} diverged from snapshot",
+                snapshot.cfg.id
+            );
+            game.cfg = snapshot.cfg;
+            game.board = board;
+            game.state = snapshot.state;
+            game.log = snapshot.log;
+            // The seeds of past rounds may have been published already.
+            game.seeds = StdRng::from_entropy();
+        }
+        // The chat is not part of the log.
+        game.chat = snapshot.chat;
+        game.reset_deadline();
+        game
+    }
+
+    pub fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            cfg: self.cfg.clone(),
+            board: self.board.clone(),
+            state: self.state.clone(),
+            log: self.log.clone(),
+            chat: self.chat.clone(),
         }
     }
 
-    pub fn notify(&self) -> Arc<Notify> {
-        self.notify.clone()
+    pub fn log(&self) -> &ActionLog {
+        &self.log
     }
 
+    pub fn id(&self) -> &str {
+        &self.cfg.id
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.cfg
+    }
+
+    pub fn is_running(&self) -> bool {
+        !matches!(
+            self.state,
+            DynamicState::Uninit | DynamicState::GameOver { .. }
+        )
+    }
+
+    pub fn is_over(&self) -> bool {
+        matches!(self.state, DynamicState::GameOver { .. })
+    }
+
+    pub fn player_count(&self) -> usize {
+        self.board.players.len()
+    }
+
+    /// The number of changes made to the game since it was created or restored.
+    pub fn version(&self) -> u64 {
+        *self.updates.borrow()
+    }
+
+    /// Subscribes to changes of the game. The receiver only keeps the latest
+    /// version, so subscribers that fall behind skip straight to it.
+    pub fn subscribe(&self) -> watch::Receiver<u64> {
+        self.updates.subscribe()
+    }
+
+    /// Publishes a new version of the game to all subscribers.
+    pub(crate) fn changed(&self) {
+        self.updates.send_modify(|version| *version += 1);
+    }
+
+    /// Seats a new player. Only possible in the lobby, before the game starts.
     #[instrument(name = "game", fields(id = %self.cfg.id), skip(self, user))]
-    pub fn add_new_user(&mut self, user: User) -> Result<Player, String> {
-        let player = Player::new(user);
-        if self.board.players.iter().any(|p| p.id == player.id) {
-            return Err("Player already exists".to_string());
+    pub fn add_new_user(&mut self, mut user: User) -> Result<Player, ActionError> {
+        if self.is_running() {
+            return Err(ActionError::WrongPhase);
         }
+        user.name = self.check_name(&user.name, None)?;
+        let player = Player::new(user);
 
         tracing::info!("new player {:?} joined", player.user);
-        self.board.players.push(player.clone());
-        self.notify.notify_waiters();
+        self.seat(player.clone());
         Ok(player)
     }
 
-    pub fn join_existing_user(&mut self, auth_token: String) -> Result<Player, String> {
+    /// Seats a bot named `name`, or a generic name if `None`.
+    #[instrument(name = "game", fields(id = %self.cfg.id), skip(self))]
+    pub fn add_bot(&mut self, name: Option<String>) -> Result<Player, ActionError> {
+        if self.is_running() {
+            return Err(ActionError::WrongPhase);
+        }
+
+        let name = match name {
+            Some(name) => self.check_name(&name, None)?,
+            None => (1..)
+                .map(|n| format!("Bot {n}"))
+                .find(|name| self.check_name(name, None).is_ok())
+                .unwrap(),
+        };
+        let player = Player::bot(User {
+            image: format!("p{}.png", self.board.players.len() % 10),
+            color: "grey".to_string(),
+            name,
+        });
+
+        tracing::info!("bot {:?} joined", player.user);
+        self.seat(player.clone());
+        Ok(player)
+    }
+
+    pub fn players(&self) -> &[Player] {
+        &self.board.players
+    }
+
+    /// Changes the name, avatar or colour of `player`. Only possible in the
+    /// lobby, before the game starts.
+    #[instrument(name = "game", fields(id = %self.cfg.id), skip(self))]
+    pub fn edit_user(&mut self, player: PlayerId, mut user: User) -> Result<(), ActionError> {
+        let index = self.board.index(&player).ok_or(ActionError::NotAPlayer)?;
+        if self.is_running() {
+            return Err(ActionError::WrongPhase);
+        }
+        user.name = self.check_name(&user.name, Some(&player))?;
+
+        tracing::info!("{player:?} is now {user:?}");
+        self.board.players[index].user = user.clone();
+        self.log.entries.push(LogEntry::EditedUser { player, user });
+        self.changed();
+        Ok(())
+    }
+
+    /// Returns `name` trimmed, if nobody but `player` goes by it already.
+    fn check_name(&self, name: &str, player: Option<&PlayerId>) -> Result<String, ActionError> {
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > MAX_NAME_LENGTH {
+            return Err(ActionError::InvalidName);
+        }
+        let taken =
+            self.board.players.iter().any(|p| {
+                Some(&p.id) != player && p.user.name.to_lowercase() == name.to_lowercase()
+            });
+        if taken {
+            return Err(ActionError::NameTaken(name.to_string()));
+        }
+        Ok(name.to_string())
+    }
+
+    fn seat(&mut self, player: Player) {
+        self.log.entries.push(LogEntry::Joined(player.clone()));
+        self.board.players.push(player);
+        self.assign_host();
+        self.changed();
+    }
+
+    /// Rejoins the player holding `auth_token`. A player who is still
+    /// connected, e.g. in another tab, rejoins as well; see [`GameState::connect`].
+    pub fn join_existing_user(&mut self, auth_token: String) -> Result<Player, ActionError> {
         let Some(player) = self
             .board
             .players
             .iter()
             .find(|p| p.access_key == auth_token)
         else {
-            return Err("No such auth token".to_string());
+            return Err(ActionError::UnknownAccessKey);
         };
 
-        if player.connected {
-            return Err("Already connected".to_string());
+        tracing::info!("reconnected player {:?}", player.user);
+        let player = player.clone();
+        self.rejoin(&player.id);
+        Ok(player)
+    }
+
+    /// Records that `id` came back and marks them connected.
+    pub(super) fn rejoin(&mut self, id: &PlayerId) {
+        self.log.entries.push(LogEntry::Rejoined(id.clone()));
+        self.reconnect(id);
+    }
+
+    fn reconnect(&mut self, id: &PlayerId) {
+        if let Some(player) = self.board.players.iter_mut().find(|p| &p.id == id) {
+            player.connected = true;
+            self.changed();
         }
+    }
 
-        tracing::info!("reconnected player {:?}", player.user);
-        self.notify.notify_waiters();
-        Ok(player.clone())
+    /// Checks whether the game could be started right now.
+    pub fn can_start(&self) -> Result<(), StartError> {
+        if self.is_running() {
+            return Err(StartError::AlreadyRunning);
+        }
+        self.check_seats()
     }
 
+    fn check_seats(&self) -> Result<(), StartError> {
+        let actual = self.board.players.len();
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&actual) {
+            return Err(unplayable(actual));
+        }
+
+        let disconnected = self
+            .board
+            .players
+            .iter()
+            .filter(|p| !p.connected)
+            .map(|p| p.id.clone())
+            .collect::<Vec<_>>();
+        if !disconnected.is_empty() {
+            return Err(StartError::PlayersDisconnected(disconnected));
+        }
+        Ok(())
+    }
+
+    /// Frees the seat of `user`, who left the lobby. Nobody leaves a running
+    /// game, so there the player is only marked as disconnected.
     #[instrument(name = "game", fields(id = %self.cfg.id), skip(self, user))]
     pub fn remove_player(&mut self, user: &PlayerId) {
         if let Some(index) = self.board.players.iter().position(|p| &p.id == user) {
             tracing::info!("player {:?} removed", user);
+            self.log.entries.push(LogEntry::Left(user.clone()));
 
-            if self.state == DynamicState::Uninit {
-                self.board.players.remove(index);
-                self.notify.notify_waiters();
+            if !self.is_running() {
+                self.board.unseat(index);
+                self.sessions.remove(user);
+                self.assign_host();
+                self.changed();
             } else {
                 self.board.players[index].connected = false;
-                self.notify.notify_waiters()
+                self.changed();
             }
         }
     }
 }
 
+impl GameSnapshot {
+    pub fn id(&self) -> &str {
+        &self.cfg.id
+    }
+}
+
 impl DynamicState {
     pub fn is_player_elect(&self, player_id: &PlayerId) -> bool {
         use DynamicState::*;
@@ -156,8 +433,8 @@ impl DynamicState {
 }
 
 impl BoardState {
-    pub fn index(&self, id: &PlayerId) -> usize {
-        self.players.iter().position(|p| &p.id == id).unwrap()
+    pub fn index(&self, id: &PlayerId) -> Option<usize> {
+        self.players.iter().position(|p| &p.id == id)
     }
 
     pub fn players_alive(&self) -> impl Iterator<Item = &Player> {
@@ -166,12 +443,11 @@ impl BoardState {
 
     pub fn new(users: Vec<User>) -> Self {
         Self {
-            players: users.into_iter().map(|u| Player::new(u)).collect(),
+            players: users.into_iter().map(Player::new).collect(),
 
-            draw_pile: Vec::new(),
-            discard_pile: Vec::new(),
+            deck: Deck::default(),
 
-            executive_actions: [None; 6],
+            executive_actions: Vec::new(),
             voting_result: None,
 
             passed_fasho_laws: 0,
@@ -183,93 +459,186 @@ impl BoardState {
             previous_chancellor: None,
 
             current_president: String::new(),
-            next_president_by_rules: None,
-            revealed_factions: None,
+            rotation: String::new(),
+            investigations: Vec::new(),
             history: Vec::new(),
+
+            host: None,
+            paused: false,
         }
     }
 
-    pub fn draw_laws<const N: usize>(&mut self) -> [Law; N] {
-        if self.draw_pile.len() < N {
-            self.draw_pile.append(&mut self.discard_pile);
-            self.draw_pile.shuffle(&mut thread_rng());
+    /// Forgets the last round, keeping only the seats and the host.
+    pub(super) fn reset(&mut self) {
+        let mut players = std::mem::take(&mut self.players);
+        for player in &mut players {
+            player.role = Role::Liberal;
+            player.alive = true;
+        }
+        *self = BoardState {
+            players,
+            host: self.host.take(),
+            ..BoardState::new(Vec::new())
+        };
+    }
+
+    /// Frees the seat at `index` between rounds. The finished round keeps
+    /// being shown, so whatever it recorded about the player is forgotten.
+    pub(super) fn unseat(&mut self, index: usize) {
+        let player = self.players.remove(index);
+        self.investigations
+            .retain(|(knower, target, _)| *knower != player.id && *target != player.id);
+        if let Some(next) = self.players.get(index % self.players.len().max(1)) {
+            let next = next.id.clone();
+            for seat in [&mut self.rotation, &mut self.current_president] {
+                if *seat == player.id {
+                    seat.clone_from(&next);
+                }
+            }
         }
-        let mut result = [Law::Fasho; N];
-        result.copy_from_slice(&self.draw_pile[..3]);
-        for i in 0..N {
-            self.draw_pile.remove(0);
+    }
+
+    fn pass(&mut self, law: Law) {
+        match law {
+            Law::Liberal => self.passed_liberal_laws += 1,
+            Law::Fasho => self.passed_fasho_laws += 1,
         }
-        result
+        // Only an enacted law resets the tracker, so vetoes add up.
+        self.no_goverment_counter = 0;
     }
 
-    pub fn play_law(&mut self, law: Law, chancellor: Option<PlayerId>) -> DynamicState {
+    /// Enacts the law chosen by the government and grants the president the
+    /// executive action of its slot on the fascist track, if any.
+    pub fn play_law(&mut self, law: Law, chancellor: PlayerId, rules: &Rules) -> DynamicState {
         self.history.push(Event::PlayedLaw {
             president: self.current_president.clone(),
-            chancellor: chancellor.clone(),
+            chancellor: Some(chancellor.clone()),
             law,
         });
+        self.pass(law);
+
+        let executive_action = match law {
+            Law::Liberal => None,
+            Law::Fasho => self
+                .executive_actions
+                .get(self.passed_fasho_laws - 1)
+                .copied()
+                .flatten(),
+        };
+        let Some(executive_action) = executive_action else {
+            return self.select_next_president(rules);
+        };
 
-        match law {
-            Law::Liberal => {
-                self.passed_liberal_laws += 1;
-                self.select_next_president()
-            }
-            Law::Fasho => {
-                self.passed_fasho_laws += 1;
-                if let Some(chancellor) = chancellor {
-                    let executive_action = self.executive_actions[self.passed_fasho_laws - 1];
-                    if let Some(executive_action) = executive_action {
-                        let action = match executive_action {
-                            ExecutiveAction::RevealFaction => ExecutiveActionTask::RevealFaction,
-                            ExecutiveAction::RevealNextCards => {
-                                ExecutiveActionTask::RevealNextCards(
-                                    self.draw_pile[..3.min(self.draw_pile.len())].to_vec(),
-                                )
-                            }
-                            ExecutiveAction::DeterminePresident => {
-                                ExecutiveActionTask::DeterminePresident
-                            }
-                            ExecutiveAction::Kill => ExecutiveActionTask::Kill,
-                        };
-
-                        DynamicState::ExecutiveAction { action, chancellor }
-                    } else {
-                        self.select_next_president()
-                    }
-                } else {
-                    self.no_goverment_counter = 0;
-                    self.select_next_president()
-                }
+        let targets = self.executive_targets(executive_action);
+        let action = match executive_action {
+            ExecutiveAction::RevealNextCards => {
+                ExecutiveActionTask::RevealNextCards(self.deck.peek())
             }
+            // A power without anyone to use it on is forfeited.
+            _ if targets.is_empty() => return self.select_next_president(rules),
+            ExecutiveAction::RevealFaction => ExecutiveActionTask::RevealFaction(targets),
+            ExecutiveAction::DeterminePresident => ExecutiveActionTask::DeterminePresident(targets),
+            ExecutiveAction::Kill => ExecutiveActionTask::Kill(targets),
+        };
+        DynamicState::ExecutiveAction { action, chancellor }
+    }
+
+    /// The players the president may use `action` on: anyone else alive,
+    /// but nobody is investigated twice.
+    pub fn executive_targets(&self, action: ExecutiveAction) -> Vec<PlayerId> {
+        self.players_alive()
+            .filter(|p| p.id != self.current_president)
+            .filter(|p| {
+                action != ExecutiveAction::RevealFaction
+                    || self
+                        .investigations
+                        .iter()
+                        .all(|(_, target, _)| *target != p.id)
+            })
+            .map(|p| p.id.clone())
+            .collect()
+    }
+
+    /// Enacts the top law of the deck after too many failed elections. The
+    /// election tracker and term limits are reset, and no executive action
+    /// is granted.
+    pub fn play_chaos(&mut self, rng: &mut impl Rng, rules: &Rules) -> DynamicState {
+        let [law] = self.deck.draw();
+        self.deck.end_session(rng);
+
+        self.history.push(Event::Chaos { law });
+        self.pass(law);
+
+        self.previous_president = None;
+        self.previous_chancellor = None;
+        self.select_next_president(rules)
+    }
+
+    /// Advances the election tracker after a failed vote or a veto, falling
+    /// into chaos once it reaches the limit.
+    pub fn fail_government(&mut self, rng: &mut impl Rng, rules: &Rules) -> DynamicState {
+        self.no_goverment_counter += 1;
+        if self.no_goverment_counter >= rules.election_tracker_limit {
+            self.play_chaos(rng, rules)
+        } else {
+            self.select_next_president(rules)
         }
     }
 
-    pub fn select_next_president(&mut self) -> DynamicState {
-        let president = self.determine_next_president_in_line();
-        self.select_president(president)
+    /// The members of the last elected government that may not be nominated
+    /// as chancellor. In small games only the last chancellor is barred.
+    pub fn term_limited(&self, rules: &Rules) -> Vec<&PlayerId> {
+        let small_game = self.players_alive().count() <= rules.small_game_size;
+        let president = self.previous_president.as_ref().filter(|_| !small_game);
+        president
+            .into_iter()
+            .chain(self.previous_chancellor.as_ref())
+            .collect()
+    }
+
+    /// Passes the presidency on to the next player in seat order.
+    pub fn select_next_president(&mut self, rules: &Rules) -> DynamicState {
+        let president = self.next_in_rotation();
+        self.rotation = president.clone();
+        self.select_president(president, rules)
     }
 
-    pub fn select_president(&mut self, president: PlayerId) -> DynamicState {
+    /// Makes `president` the president for one term, after which the
+    /// presidency returns to the seat order where it left off.
+    pub fn special_election(&mut self, president: PlayerId, rules: &Rules) -> DynamicState {
+        self.select_president(president, rules)
+    }
+
+    pub fn select_president(&mut self, president: PlayerId, rules: &Rules) -> DynamicState {
         self.current_president = president;
-        let options = self
+        let candidates = self
             .players_alive()
             .map(|p| p.id.clone())
             .filter(|id| *id != self.current_president)
-            .filter(|id| {
-                Some(id) != self.previous_president.as_ref()
-                    && Some(id) != self.previous_chancellor.as_ref()
-            })
+            .collect::<Vec<_>>();
+        let term_limited = self.term_limited(rules);
+        let options = candidates
+            .iter()
+            .filter(|id| !term_limited.contains(id))
+            .cloned()
             .collect::<Vec<_>>();
 
+        // Never leave the president without a candidate.
+        let options = if options.is_empty() {
+            candidates
+        } else {
+            options
+        };
         DynamicState::ChooseChancellor { options }
     }
 
-    pub fn determine_next_president_in_line(&mut self) -> PlayerId {
-        if let Some(next) = self.next_president_by_rules.take() {
-            return next;
-        }
-
-        let mut idx = self.index(&self.current_president) + 1;
+    /// The next alive player to the left of the last regular president.
+    pub fn next_in_rotation(&self) -> PlayerId {
+        let mut idx = self
+            .index(&self.rotation)
+            .or_else(|| self.index(&self.current_president))
+            .expect("current president must be seated")
+            + 1;
         while !self.players[idx % self.players.len()].alive {
             idx += 1;
         }
@@ -277,18 +646,56 @@ impl BoardState {
     }
 }
 
+/// Why a game cannot be played with `actual` players.
+fn unplayable(actual: usize) -> StartError {
+    if actual < MIN_PLAYERS {
+        StartError::TooFewPlayers {
+            min: MIN_PLAYERS,
+            actual,
+        }
+    } else {
+        StartError::TooManyPlayers {
+            max: MAX_PLAYERS,
+            actual,
+        }
+    }
+}
+
 impl GameState {
-    pub fn start(&mut self) {
+    /// Deals a new round. Every round is played with an RNG of its own,
+    /// so that the published seed of a finished round does not predict
+    /// the next one.
+    ///
+    /// Leaves the game untouched if it cannot be played with the seated players.
+    pub fn start(&mut self) -> Result<(), StartError> {
         let player_count = self.board.players.len();
-        let roles = Role::roles_for(player_count);
-        for (player, role) in self.board.players.iter_mut().zip(roles.into_iter()) {
+        let executive_actions = self
+            .cfg
+            .rules
+            .executive_track_for(player_count)
+            .ok_or_else(|| unplayable(player_count))?;
+
+        let mut seeds = self.seeds.clone();
+        let seed = seeds.gen();
+        let seed = self.next_seed.unwrap_or(seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let roles =
+            Role::roles_for(player_count, &mut rng).ok_or_else(|| unplayable(player_count))?;
+
+        self.seeds = seeds;
+        self.next_seed = None;
+        self.rng = rng;
+        self.log.entries.push(LogEntry::Seeded(seed));
+
+        for (player, role) in self.board.players.iter_mut().zip(roles) {
             player.role = role;
+            player.alive = true;
         }
 
-        self.board.executive_actions = ExecutiveAction::for_player_count(player_count);
+        let rules = &self.cfg.rules;
+        self.board.executive_actions = executive_actions;
 
-        self.board.draw_pile = Law::full_draw_pile();
-        self.board.discard_pile = Vec::new();
+        self.board.deck = Deck::new(rules.liberal_laws, rules.fasho_laws, &mut self.rng);
 
         self.board.passed_fasho_laws = 0;
         self.board.passed_liberal_laws = 0;
@@ -296,13 +703,15 @@ impl GameState {
         self.board.no_goverment_counter = 0;
 
         self.board.previous_president = None;
-        self.board.previous_president = None;
+        self.board.previous_chancellor = None;
 
+        self.board.voting_result = None;
+        self.board.investigations = Vec::new();
         self.board.history = Vec::new();
 
-        let idx = random::<usize>() % self.board.players.len();
+        let idx = self.rng.gen_range(0..self.board.players.len());
         self.board.current_president = self.board.players[idx].id.clone();
-        self.board.next_president_by_rules = None;
+        self.board.rotation = self.board.current_president.clone();
 
         // TODO: initiate first action
         let ids = self
@@ -313,14 +722,19 @@ impl GameState {
             .filter(|id| *id != self.board.current_president)
             .collect::<Vec<_>>();
         self.state = DynamicState::ChooseChancellor { options: ids };
+        Ok(())
     }
 
-    pub fn check_win_conditions(&self) -> Option<Win> {
-        if self.board.passed_fasho_laws == 6 {
-            return Some(Win::Fasho);
+    pub fn check_win_conditions(&self) -> Option<(Win, WinReason)> {
+        if !self.is_running() {
+            return None;
+        }
+        let rules = &self.cfg.rules;
+        if self.board.passed_fasho_laws >= rules.fasho_track {
+            return Some((Win::Fasho, WinReason::FashoLaws));
         }
-        if self.board.passed_liberal_laws == 5 {
-            return Some(Win::Liberal);
+        if self.board.passed_liberal_laws >= rules.liberal_track {
+            return Some((Win::Liberal, WinReason::LiberalLaws));
         }
 
         if let Some(hitler) = self
@@ -328,27 +742,31 @@ impl GameState {
             .players_alive()
             .find(|p| p.role == Role::FashoHitler)
         {
-            // TODO
-            if self.board.passed_fasho_laws >= 3 && self.state.is_player_elect(&hitler.id) {
-                Some(Win::Fasho)
+            if self.board.passed_fasho_laws >= rules.hitler_threshold
+                && self.state.is_player_elect(&hitler.id)
+            {
+                Some((Win::Fasho, WinReason::HitlerElected))
             } else {
                 None
             }
         } else {
-            Some(Win::Liberal)
+            Some((Win::Liberal, WinReason::HitlerKilled))
         }
     }
 
     pub fn tasks(&self, player_id: &PlayerId) -> Option<Task> {
+        if self.board.paused || !self.board.players_alive().any(|p| &p.id == player_id) {
+            return None;
+        }
         match &self.state {
-            DynamicState::Uninit => None,
+            DynamicState::Uninit | DynamicState::GameOver { .. } => None,
             DynamicState::ChooseChancellor { options } => (*player_id
                 == self.board.current_president)
                 .then(|| Task::ChooseChancellor(options.clone())),
             DynamicState::VoteChancellor {
                 chancellor: canidate,
                 votes,
-            } => votes.get(player_id).unwrap().is_none().then(|| {
+            } => votes.get(player_id).is_some_and(Option::is_none).then(|| {
                 Task::Vote(VotingProposal {
                     president: self.board.current_president.clone(),
                     chancellor: canidate.clone(),
@@ -364,7 +782,7 @@ impl GameState {
             } => (player_id == chancellor).then(|| Task::PickLaws(laws.to_vec(), *can_ask_veto)),
 
             DynamicState::AskVeto { .. } => {
-                (*player_id == self.board.current_president).then(|| Task::ConfirmVeto)
+                (*player_id == self.board.current_president).then_some(Task::ConfirmVeto)
             }
 
             DynamicState::ExecutiveAction { action, .. } => (*player_id
@@ -373,19 +791,72 @@ impl GameState {
         }
     }
 
+    /// Checks that `player` holds the [`Task`] that `action` resolves.
+    fn authorize(&self, player: &PlayerId, action: &TaskAction) -> Result<(), ActionError> {
+        let Some(seat) = self.board.players.iter().find(|p| &p.id == player) else {
+            return Err(ActionError::NotAPlayer);
+        };
+
+        match action {
+            TaskAction::Start => {
+                self.authorize_host(player)?;
+                return self.can_start().map_err(ActionError::CannotStart);
+            }
+            TaskAction::Restart => {
+                self.authorize_host(player)?;
+                if !self.is_running() {
+                    return Err(ActionError::WrongPhase);
+                }
+                return self.check_seats().map_err(ActionError::CannotStart);
+            }
+            _ => {}
+        }
+
+        if !seat.alive {
+            return Err(ActionError::Dead);
+        }
+        if self.board.paused {
+            return Err(ActionError::Paused);
+        }
+
+        match (self.tasks(player), action) {
+            (None, _) => Err(ActionError::NotYourTurn),
+            (Some(Task::ChooseChancellor(_)), TaskAction::ChooseChancellor(_))
+            | (Some(Task::Vote(_)), TaskAction::Vote(_))
+            | (Some(Task::PickLaws(..)), TaskAction::PickedLaws(..) | TaskAction::Veto(_))
+            | (Some(Task::ConfirmVeto), TaskAction::Veto(_))
+            | (Some(Task::ExecutiveAction(_)), TaskAction::ExecuteAction(_)) => Ok(()),
+            (Some(_), _) => Err(ActionError::WrongPhase),
+        }
+    }
+
+    /// Applies an action of `player` and records it in the action log,
+    /// if it was accepted.
     #[instrument(name = "game", fields(id = %self.cfg.id), skip(self, action))]
-    pub fn on_action(&mut self, player: PlayerId, action: TaskAction) {
+    pub fn on_action(&mut self, player: PlayerId, action: TaskAction) -> Result<(), ActionError> {
+        self.seen(&player);
+        self.apply_action(player.clone(), action.clone())?;
+        self.log.entries.push(LogEntry::Action { player, action });
+        Ok(())
+    }
+
+    fn apply_action(&mut self, player: PlayerId, action: TaskAction) -> Result<(), ActionError> {
         use TaskAction::*;
+        self.authorize(&player, &action)?;
+
         let new_state = match (self.state.clone(), action) {
-            (_, Start) => {
-                self.start();
-                tracing::info!("Staring game");
-                self.notify.notify_waiters();
-                return;
+            (_, Start | Restart) => {
+                self.start().map_err(ActionError::CannotStart)?;
+                self.reset_deadline();
+                tracing::info!("Starting game");
+                self.changed();
+                return Ok(());
             }
 
             (DynamicState::ChooseChancellor { options }, ChooseChancellor(choosen)) => {
-                assert!(options.contains(&choosen));
+                if !options.contains(&choosen) {
+                    return Err(ActionError::IneligibleChancellor(choosen));
+                }
 
                 // FIXME: Quick Hack to make debugging easier
 
@@ -396,8 +867,7 @@ impl GameState {
                 self.board.voting_result = None;
 
                 if self.cfg.no_votes {
-                    self.board.no_goverment_counter = 0;
-                    let laws = self.board.draw_laws::<3>();
+                    let laws = self.board.deck.draw();
                     DynamicState::PresidentChooseLaws {
                         laws,
                         chancellor: choosen.clone(),
@@ -405,8 +875,7 @@ impl GameState {
                 } else {
                     let votes = self
                         .board
-                        .players
-                        .iter()
+                        .players_alive()
                         .map(|p| (p.id.clone(), None))
                         .collect();
                     DynamicState::VoteChancellor {
@@ -431,8 +900,11 @@ impl GameState {
                 {
                     let pros = votes.values().filter(|v| **v == Some(true)).count();
                     let cons = votes.values().filter(|v| **v == Some(false)).count();
-                    let presidents_vote =
-                        votes.get(&self.board.current_president).unwrap().unwrap();
+                    let presidents_vote = votes
+                        .get(&self.board.current_president)
+                        .copied()
+                        .flatten()
+                        .unwrap_or(false);
 
                     let vote_success = pros > cons || (pros == cons && presidents_vote);
 
@@ -450,9 +922,7 @@ impl GameState {
                         Some(votes.into_iter().flat_map(|(k, v)| Some((k, v?))).collect());
 
                     if vote_success {
-                        self.board.no_goverment_counter = 0;
-
-                        let laws = self.board.draw_laws::<3>();
+                        let laws = self.board.deck.draw();
 
                         DynamicState::PresidentChooseLaws {
                             laws,
@@ -460,40 +930,43 @@ impl GameState {
                         }
                     } else {
                         // Vote failed
-                        self.board.no_goverment_counter += 1;
-                        if self.board.no_goverment_counter == 3 {
-                            // Goverment failed: autoplay card
-                            let law = self.board.draw_laws::<1>()[0];
-                            self.board.play_law(law, None)
-                        } else {
-                            self.board.select_next_president()
-                        }
+                        self.board.fail_government(&mut self.rng, &self.cfg.rules)
                     }
                 } else {
                     DynamicState::VoteChancellor { chancellor, votes }
                 }
             }
 
-            (DynamicState::PresidentChooseLaws { chancellor, .. }, PickedLaws(keep, discard)) => {
-                // Assert true
-                self.board.discard_pile.push(discard);
+            (DynamicState::PresidentChooseLaws { chancellor, laws }, PickedLaws(keep, discard)) => {
+                if !is_selection_of(&laws, &keep, discard) {
+                    return Err(ActionError::BadLawSelection);
+                }
+                let laws = keep.try_into().map_err(|_| ActionError::BadLawSelection)?;
+                self.board.deck.discard(&[discard]);
                 self.board.voting_result = None;
                 DynamicState::ChancellorChooseLaws {
-                    laws: keep.try_into().unwrap(),
-                    can_ask_veto: self.board.passed_fasho_laws == 5,
+                    laws,
+                    can_ask_veto: self.board.passed_fasho_laws >= self.cfg.rules.veto_threshold,
                     chancellor,
                 }
             }
 
-            (DynamicState::ChancellorChooseLaws { chancellor, .. }, PickedLaws(keep, discard)) => {
-                // Assert true
-                assert_eq!(keep.len(), 1);
-                self.board.discard_pile.push(discard);
+            (
+                DynamicState::ChancellorChooseLaws {
+                    chancellor, laws, ..
+                },
+                PickedLaws(keep, discard),
+            ) => {
+                if !is_selection_of(&laws, &keep, discard) {
+                    return Err(ActionError::BadLawSelection);
+                }
+                self.board.deck.discard(&[discard]);
+                self.board.deck.end_session(&mut self.rng);
 
                 self.board.previous_president = Some(self.board.current_president.clone());
                 self.board.previous_chancellor = Some(chancellor.clone());
 
-                self.board.play_law(keep[0], Some(chancellor))
+                self.board.play_law(keep[0], chancellor, &self.cfg.rules)
             }
 
             (
@@ -503,10 +976,15 @@ impl GameState {
                     can_ask_veto,
                 },
                 Veto(true),
-            ) if can_ask_veto => DynamicState::AskVeto {
-                chancellor: chancellor.clone(),
-                laws,
-            },
+            ) => {
+                if !can_ask_veto {
+                    return Err(ActionError::VetoLocked);
+                }
+                DynamicState::AskVeto {
+                    chancellor: chancellor.clone(),
+                    laws,
+                }
+            }
 
             (DynamicState::AskVeto { chancellor, laws }, Veto(accept)) => {
                 if accept {
@@ -515,12 +993,13 @@ impl GameState {
                         chancellor: chancellor.clone(),
                     });
 
-                    self.board.discard_pile.extend_from_slice(&laws);
+                    self.board.deck.discard(&laws);
+                    self.board.deck.end_session(&mut self.rng);
 
                     self.board.previous_president = Some(self.board.current_president.clone());
                     self.board.previous_chancellor = Some(chancellor.clone());
 
-                    self.board.select_next_president()
+                    self.board.fail_government(&mut self.rng, &self.cfg.rules)
                 } else {
                     DynamicState::ChancellorChooseLaws {
                         can_ask_veto: false,
@@ -532,40 +1011,52 @@ impl GameState {
 
             (
                 DynamicState::ExecutiveAction {
-                    action: ExecutiveActionTask::Kill,
+                    action: ExecutiveActionTask::Kill(targets),
                     ..
                 },
                 ExecuteAction(ExecutiveActionResponse::Kill(target)),
             ) => {
-                let idx = self.board.index(&target);
+                let idx = self
+                    .board
+                    .index(&target)
+                    .filter(|_| targets.contains(&target))
+                    .ok_or(ActionError::InvalidTarget(target))?;
                 self.board.players[idx].alive = false;
-                self.board.select_next_president()
+                self.board.select_next_president(&self.cfg.rules)
             }
 
             (
                 DynamicState::ExecutiveAction {
-                    action: ExecutiveActionTask::RevealFaction,
+                    action: ExecutiveActionTask::RevealFaction(targets),
                     ..
                 },
                 ExecuteAction(ExecutiveActionResponse::RevealFaction(target)),
             ) => {
-                let idx = self.board.index(&target);
+                let idx = self
+                    .board
+                    .index(&target)
+                    .filter(|_| targets.contains(&target))
+                    .ok_or_else(|| ActionError::InvalidTarget(target.clone()))?;
                 let faction = self.board.players[idx].role.faction();
-                self.board.revealed_factions =
-                    Some((self.board.current_president.clone(), target, faction));
-                self.board.select_next_president()
+                self.board.investigations.push((
+                    self.board.current_president.clone(),
+                    target,
+                    faction,
+                ));
+                self.board.select_next_president(&self.cfg.rules)
             }
 
             (
                 DynamicState::ExecutiveAction {
-                    action: ExecutiveActionTask::DeterminePresident,
+                    action: ExecutiveActionTask::DeterminePresident(targets),
                     ..
                 },
                 ExecuteAction(ExecutiveActionResponse::DeterminePresident(target)),
             ) => {
-                self.board.next_president_by_rules =
-                    Some(self.board.determine_next_president_in_line());
-                self.board.select_president(target)
+                if !targets.contains(&target) {
+                    return Err(ActionError::InvalidTarget(target));
+                }
+                self.board.special_election(target, &self.cfg.rules)
             }
 
             (
@@ -574,16 +1065,41 @@ impl GameState {
                     ..
                 },
                 ExecuteAction(ExecutiveActionResponse::RevealNextCards),
-            ) => self.board.select_next_president(),
+            ) => self.board.select_next_president(&self.cfg.rules),
 
-            (_, action) => todo!("unknown state and action combo: {:?} {:?}", self, action),
+            (_, action) => {
+                tracing::warn!("action {action:?} not allowed in state {:?}", self.state);
+                return Err(ActionError::WrongPhase);
+            }
         };
 
         let has_changed = new_state != self.state;
-        self.state = new_state;
+        let previous = std::mem::replace(&mut self.state, new_state);
+
+        if let Some((winner, reason)) = self.check_win_conditions() {
+            tracing::info!("Game over: {winner:?} won by {reason:?}");
+            self.board.history.push(Event::GameOver { winner, reason });
+            self.state = DynamicState::GameOver { winner, reason };
+        }
+        self.update_deadline(&previous);
+
         if has_changed {
             tracing::info!("State: {:?}", self.state);
-            self.notify.notify_waiters();
+            self.changed();
         }
+        Ok(())
+    }
+}
+
+/// Checks that `keep` and `discard` together are exactly the `dealt` laws,
+/// counting duplicates.
+fn is_selection_of(dealt: &[Law], keep: &[Law], discard: Law) -> bool {
+    let mut remaining = dealt.to_vec();
+    for law in keep.iter().chain(std::iter::once(&discard)) {
+        let Some(idx) = remaining.iter().position(|l| l == law) else {
+            return false;
+        };
+        remaining.swap_remove(idx);
     }
+    remaining.is_empty()
 }
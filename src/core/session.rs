@@ -0,0 +1,84 @@
+use std::time::SystemTime;
+
+use tracing::instrument;
+
+use super::{types::PlayerId, GameState};
+
+/// Identifies one connection of a player, e.g. a browser tab.
+pub type ConnectionId = u64;
+
+/// How a player is connected to a game. Sessions are not part of the game
+/// itself: they are neither logged nor restored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerSession {
+    /// The connection the player currently plays through, if any.
+    pub connection: Option<ConnectionId>,
+    /// When the player last connected, disconnected or acted.
+    pub last_seen: SystemTime,
+    /// How often the player has connected to the game.
+    pub connections: u32,
+}
+
+impl GameState {
+    pub fn session(&self, player: &PlayerId) -> Option<&PlayerSession> {
+        self.sessions.get(player)
+    }
+
+    /// Whether `connection` is the one `player` currently plays through.
+    pub fn is_current_connection(&self, player: &PlayerId, connection: ConnectionId) -> bool {
+        self.session(player)
+            .is_some_and(|s| s.connection == Some(connection))
+    }
+
+    /// Makes `connection` the one `player` plays through. A previous
+    /// connection is taken over and returned. Its session is told that it
+    /// was replaced and closed on the next update.
+    #[instrument(name = "game", fields(id = %self.cfg.id), skip(self))]
+    pub fn connect(&mut self, player: &PlayerId, connection: ConnectionId) -> Option<ConnectionId> {
+        self.board.index(player)?;
+        let session = self
+            .sessions
+            .entry(player.clone())
+            .or_insert_with(|| PlayerSession {
+                connection: None,
+                last_seen: SystemTime::now(),
+                connections: 0,
+            });
+        let previous = session.connection.replace(connection);
+        session.last_seen = SystemTime::now();
+        session.connections += 1;
+        if let Some(previous) = previous {
+            tracing::info!("connection {connection} took over {previous}");
+        }
+
+        self.reconnect(player);
+        previous
+    }
+
+    /// Ends `connection` of `player`. The player keeps their seat, so that
+    /// they can rejoin with their access key; only leaving or being kicked
+    /// frees it. Connections that were taken over are ignored, so that a
+    /// stale tab cannot disconnect the current one.
+    #[instrument(name = "game", fields(id = %self.cfg.id), skip(self))]
+    pub fn disconnect(&mut self, player: &PlayerId, connection: ConnectionId) {
+        if !self.is_current_connection(player, connection) {
+            return;
+        }
+        if let Some(session) = self.sessions.get_mut(player) {
+            session.connection = None;
+            session.last_seen = SystemTime::now();
+        }
+        if let Some(index) = self.board.index(player) {
+            tracing::info!("player {player:?} disconnected");
+            self.board.players[index].connected = false;
+            self.changed();
+        }
+    }
+
+    /// Notes that `player` is still around.
+    pub(super) fn seen(&mut self, player: &PlayerId) {
+        if let Some(session) = self.sessions.get_mut(player) {
+            session.last_seen = SystemTime::now();
+        }
+    }
+}
@@ -0,0 +1,122 @@
+use std::mem::discriminant;
+use std::time::{Duration, SystemTime};
+
+use rand::prelude::SliceRandom;
+use rand::Rng;
+
+use super::{
+    types::{ExecutiveActionResponse, ExecutiveActionTask, Law, PlayerId, TaskAction},
+    DynamicState, GameState,
+};
+
+impl GameState {
+    /// The point in time at which the current phase times out, if any.
+    pub fn deadline(&self) -> Option<SystemTime> {
+        self.deadline
+    }
+
+    fn phase_timeout(&self) -> Option<Duration> {
+        if self.board.paused {
+            return None;
+        }
+        let timeouts = &self.cfg.timeouts;
+        let secs = match self.state {
+            DynamicState::Uninit | DynamicState::GameOver { .. } => None,
+            DynamicState::ChooseChancellor { .. } => timeouts.choose_chancellor,
+            DynamicState::VoteChancellor { .. } => timeouts.vote,
+            DynamicState::PresidentChooseLaws { .. }
+            | DynamicState::ChancellorChooseLaws { .. }
+            | DynamicState::AskVeto { .. } => timeouts.legislative,
+            DynamicState::ExecutiveAction { .. } => timeouts.executive,
+        }?;
+        Some(Duration::from_secs(secs))
+    }
+
+    /// Starts the clock of the current phase.
+    pub(super) fn reset_deadline(&mut self) {
+        self.deadline = self.phase_timeout().map(|t| SystemTime::now() + t);
+    }
+
+    /// Restarts the clock, if `previous` was a different phase than the current one.
+    pub(super) fn update_deadline(&mut self, previous: &DynamicState) {
+        if discriminant(previous) != discriminant(&self.state) {
+            self.reset_deadline();
+        }
+    }
+
+    /// The actions taken for all players with pending tasks, once the
+    /// current phase times out.
+    pub fn default_actions(&self, rng: &mut impl Rng) -> Vec<(PlayerId, TaskAction)> {
+        let president = self.board.current_president.clone();
+        match &self.state {
+            DynamicState::Uninit | DynamicState::GameOver { .. } => Vec::new(),
+            DynamicState::ChooseChancellor { options } => options
+                .choose(rng)
+                .map(|chancellor| (president, TaskAction::ChooseChancellor(chancellor.clone())))
+                .into_iter()
+                .collect(),
+            DynamicState::VoteChancellor { votes, .. } => votes
+                .iter()
+                .filter(|(_, vote)| vote.is_none())
+                .map(|(id, _)| {
+                    let vote = self.cfg.timeouts.default_vote;
+                    (id.clone(), TaskAction::Vote(vote))
+                })
+                .collect(),
+            DynamicState::PresidentChooseLaws { laws, .. } => {
+                vec![(president, random_discard(laws, rng))]
+            }
+            DynamicState::ChancellorChooseLaws {
+                laws, chancellor, ..
+            } => vec![(chancellor.clone(), random_discard(laws, rng))],
+            DynamicState::AskVeto { .. } => vec![(president, TaskAction::Veto(false))],
+            DynamicState::ExecutiveAction { action, .. } => {
+                let response = match action {
+                    ExecutiveActionTask::Kill(targets) => targets
+                        .choose(rng)
+                        .cloned()
+                        .map(ExecutiveActionResponse::Kill),
+                    ExecutiveActionTask::RevealFaction(targets) => targets
+                        .choose(rng)
+                        .cloned()
+                        .map(ExecutiveActionResponse::RevealFaction),
+                    ExecutiveActionTask::DeterminePresident(targets) => targets
+                        .choose(rng)
+                        .cloned()
+                        .map(ExecutiveActionResponse::DeterminePresident),
+                    ExecutiveActionTask::RevealNextCards(_) => {
+                        Some(ExecutiveActionResponse::RevealNextCards)
+                    }
+                };
+                response
+                    .map(|response| (president, TaskAction::ExecuteAction(response)))
+                    .into_iter()
+                    .collect()
+            }
+        }
+    }
+
+    /// Resolves all pending tasks with their default action, if the current
+    /// phase has timed out. Returns whether the deadline had passed.
+    pub fn on_timeout(&mut self, rng: &mut impl Rng) -> bool {
+        if self.deadline.is_none_or(|d| d > SystemTime::now()) {
+            return false;
+        }
+
+        tracing::info!("timed out in state {:?}", self.state);
+        // Entering the next phase sets a new deadline.
+        self.deadline = None;
+        for (player, action) in self.default_actions(rng) {
+            if let Err(e) = self.on_action(player.clone(), action) {
+                tracing::error!("default action of {player:?} rejected: {e}");
+            }
+        }
+        true
+    }
+}
+
+fn random_discard(laws: &[Law], rng: &mut impl Rng) -> TaskAction {
+    let mut keep = laws.to_vec();
+    let discard = keep.remove(rng.gen_range(0..keep.len()));
+    TaskAction::PickedLaws(keep, discard)
+}
@@ -3,7 +3,9 @@ use rand::prelude::SliceRandom;
 use rand::{thread_rng, Rng};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::iter::{once, repeat};
+use std::iter::{once, repeat_n};
+
+use super::{MAX_PLAYERS, MIN_PLAYERS};
 
 pub type PlayerId = String;
 
@@ -24,38 +26,44 @@ pub struct Player {
 
     pub connected: bool,
     pub access_key: String,
+    #[serde(default)]
+    pub bot: bool,
 }
 
 impl Player {
+    /// A new player with a random id, so that names can repeat and change.
     pub fn new(user: User) -> Self {
         Player {
-            id: user.name.clone(),
+            id: random_key(16),
             user,
             role: Role::Liberal,
             alive: true,
 
             connected: true,
-            access_key: thread_rng()
-                .sample_iter(&Alphanumeric)
-                .take(32)
-                .map(char::from)
-                .collect::<String>(),
+            access_key: random_key(32),
+            bot: false,
         }
     }
-}
-
-pub type Law = Faction;
 
-impl Law {
-    pub fn full_draw_pile() -> Vec<Law> {
-        let liberals = repeat(Law::Liberal).take(6);
-        let fashos = repeat(Law::Fasho).take(9);
-        let mut pile = liberals.chain(fashos).collect::<Vec<_>>();
-        pile.shuffle(&mut thread_rng());
-        pile
+    /// A seat played by the server. Bots are always connected.
+    pub fn bot(user: User) -> Self {
+        Player {
+            bot: true,
+            ..Player::new(user)
+        }
     }
 }
 
+fn random_key(len: usize) -> String {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+pub type Law = Faction;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Role {
     Liberal,
@@ -70,14 +78,19 @@ pub enum Faction {
 }
 
 impl Role {
-    pub fn roles_for(n: usize) -> Vec<Role> {
+    /// Deals the roles for `n` players, or `None` if the game cannot be
+    /// played with that many.
+    pub fn roles_for(n: usize, rng: &mut impl Rng) -> Option<Vec<Role>> {
+        if !(MIN_PLAYERS..=MAX_PLAYERS).contains(&n) {
+            return None;
+        }
         let fashos = ((n - 5) / 2) + 1;
         let mut pile = once(Role::FashoHitler)
-            .chain(repeat(Role::Fasho).take(fashos))
-            .chain(repeat(Role::Liberal).take(n - fashos - 1))
+            .chain(repeat_n(Role::Fasho, fashos))
+            .chain(repeat_n(Role::Liberal, n - fashos - 1))
             .collect::<Vec<_>>();
-        pile.shuffle(&mut thread_rng());
-        pile
+        pile.shuffle(rng);
+        Some(pile)
     }
 
     pub fn faction(&self) -> Faction {
@@ -108,6 +121,9 @@ pub struct VotingProposal {
 #[serde(tag = "type", content = "value")]
 pub enum TaskAction {
     Start,
+    /// Abandons a running game and deals a new one. Only the host may
+    /// restart, so that a stray click cannot end a game in progress.
+    Restart,
     ChooseChancellor(PlayerId),
     Vote(bool),
     PickedLaws(Vec<Law>, Law),
@@ -126,9 +142,13 @@ pub enum ExecutiveAction {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(tag = "type", content = "value")]
 pub enum ExecutiveActionTask {
-    Kill,
-    RevealFaction,
-    DeterminePresident,
+    /// The players that may be killed.
+    Kill(Vec<PlayerId>),
+    /// The players that may be investigated.
+    RevealFaction(Vec<PlayerId>),
+    /// The players that may be appointed president.
+    DeterminePresident(Vec<PlayerId>),
+    /// The laws on top of the deck.
     RevealNextCards(Vec<Law>),
 }
 
@@ -142,9 +162,9 @@ pub enum ExecutiveActionResponse {
 }
 
 impl ExecutiveAction {
-    pub fn for_player_count(count: usize) -> [Option<ExecutiveAction>; 6] {
+    pub fn for_player_count(count: usize) -> Option<[Option<ExecutiveAction>; 6]> {
         use ExecutiveAction::*;
-        match count {
+        let track = match count {
             5 | 6 => [
                 None,
                 None,
@@ -169,13 +189,22 @@ impl ExecutiveAction {
                 Some(Kill),
                 None,
             ],
-            _ => unreachable!(),
-        }
+            _ => return None,
+        };
+        Some(track)
     }
 }
 
 pub type Win = Faction;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum WinReason {
+    LiberalLaws,
+    FashoLaws,
+    HitlerKilled,
+    HitlerElected,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Event {
     ChooseChancellor {
@@ -197,4 +226,12 @@ pub enum Event {
         president: PlayerId,
         chancellor: PlayerId,
     },
+    /// Too many elections failed in a row, so the top law was enacted.
+    Chaos {
+        law: Law,
+    },
+    GameOver {
+        winner: Win,
+        reason: WinReason,
+    },
 }
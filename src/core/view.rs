@@ -1,103 +1,219 @@
-use std::{collections::HashMap, fmt::Debug};
+use std::{collections::HashMap, fmt::Debug, time::UNIX_EPOCH};
 
 use serde::{Deserialize, Serialize};
 
 use super::{
-    types::{ExecutiveAction, Faction, Player, PlayerId, Role, User},
+    cfg::Rules,
+    types::{ExecutiveAction, Faction, Player, PlayerId, Role, User, Win, WinReason},
     BoardState, DynamicState, Event, ExecutiveActionTask, GameState,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GameView {
-    board: BoardStateView,
-    state: StateView,
-    me: PlayerView,
+    pub board: BoardStateView,
+    pub state: StateView,
+    pub me: Option<PlayerView>,
+    pub rules: Rules,
 }
 
+/// Who a [`GameView`] is rendered for.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum Viewer {
+    Player(PlayerId),
+    Spectator(SpectatorMode),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpectatorMode {
+    /// Sees only what is public to everyone at the table.
+    Public,
+    /// Sees all roles and cards. Only available if enabled in the config.
+    Omniscient,
+}
+
+/// The phases in which players act carry the `deadline` at which they time
+/// out, as a Unix timestamp in milliseconds.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(tag = "type", content = "value")]
 pub enum StateView {
     Uninit,
     ChooseChancellor {
         options: Vec<PlayerId>,
+        deadline: Option<u64>,
     },
     VoteChancellor {
         canidate: PlayerId,
+        deadline: Option<u64>,
     },
     PresidentChooseLaws {
         chancellor: PlayerId,
+        deadline: Option<u64>,
     },
     ChancellorChooseLaws {
         can_ask_veto: bool,
         chancellor: PlayerId,
+        deadline: Option<u64>,
     },
     ExecutiveAction {
         chancellor: PlayerId,
         action: ExecutiveActionTask,
+        deadline: Option<u64>,
     },
     AskVeto {
         chancellor: PlayerId,
+        deadline: Option<u64>,
+    },
+    GameOver {
+        winner: Win,
+        reason: WinReason,
     },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BoardStateView {
-    players: Vec<PlayerView>,
+    pub players: Vec<PlayerView>,
 
-    draw_pile: usize,
-    discard_pile: usize,
+    pub draw_pile: usize,
+    pub discard_pile: usize,
 
-    executive_actions: [Option<ExecutiveAction>; 6],
-    voting_result: Option<HashMap<PlayerId, bool>>,
+    pub executive_actions: Vec<Option<ExecutiveAction>>,
+    pub voting_result: Option<HashMap<PlayerId, bool>>,
 
-    passed_fasho_laws: usize,
-    passed_liberal_laws: usize,
+    pub passed_fasho_laws: usize,
+    pub passed_liberal_laws: usize,
 
-    no_goverment_counter: usize,
+    pub no_goverment_counter: usize,
 
-    previous_president: Option<PlayerId>,
-    previous_chancellor: Option<PlayerId>,
+    pub previous_president: Option<PlayerId>,
+    pub previous_chancellor: Option<PlayerId>,
 
-    current_president: PlayerId,
-    next_president_by_rules: Option<PlayerId>,
-    history: Vec<Event>,
+    pub current_president: PlayerId,
+    /// Who becomes president once the current special election is over.
+    pub next_president_by_rules: Option<PlayerId>,
+    pub history: Vec<Event>,
+    pub paused: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct PlayerView {
-    id: PlayerId,
-    user: User,
-    alive: bool,
-    role: Option<Role>,
-    faction: Option<Faction>,
+    pub id: PlayerId,
+    pub user: User,
+    pub alive: bool,
+    pub role: Option<Role>,
+    pub faction: Option<Faction>,
+    pub bot: bool,
+    pub host: bool,
+    /// Whether the player is connected. Bots always are.
+    pub connected: bool,
+    /// When the player was last seen, in milliseconds since the Unix epoch.
+    /// `None` for bots and for players who have not connected since the
+    /// server started.
+    pub last_seen: Option<u64>,
 }
 
 impl GameState {
     pub fn view(&self, of: &PlayerId) -> Option<GameView> {
         let player = self.board.players.iter().find(|p| &p.id == of)?;
+        let reveal_all = matches!(self.state, DynamicState::GameOver { .. });
+        let mut me = player.view(Some(player), false, reveal_all, self.is_host(of));
+        me.last_seen = self.last_seen_millis(of);
         Some(GameView {
-            board: self.board.view(player),
-            state: self.state.view(player),
-            me: player.view(&player, false),
+            board: self.with_presence(self.board.view(Some(player), reveal_all)),
+            state: self.state.view(
+                reveal_all || *of == self.board.current_president,
+                self.deadline_millis(),
+            ),
+            me: Some(me),
+            rules: self.cfg.rules.clone(),
         })
     }
+
+    /// The view of someone watching the game without taking part in it.
+    pub fn spectator_view(&self, mode: SpectatorMode) -> GameView {
+        let reveal_all = mode == SpectatorMode::Omniscient
+            || matches!(self.state, DynamicState::GameOver { .. });
+        GameView {
+            board: self.with_presence(self.board.view(None, reveal_all)),
+            state: self.state.view(reveal_all, self.deadline_millis()),
+            me: None,
+            rules: self.cfg.rules.clone(),
+        }
+    }
+
+    fn is_host(&self, id: &PlayerId) -> bool {
+        self.board.host.as_ref() == Some(id)
+    }
+
+    fn deadline_millis(&self) -> Option<u64> {
+        let since_epoch = self.deadline?.duration_since(UNIX_EPOCH).ok()?;
+        Some(since_epoch.as_millis() as u64)
+    }
+
+    fn last_seen_millis(&self, id: &PlayerId) -> Option<u64> {
+        let last_seen = self.session(id)?.last_seen;
+        let since_epoch = last_seen.duration_since(UNIX_EPOCH).ok()?;
+        Some(since_epoch.as_millis() as u64)
+    }
+
+    fn with_presence(&self, mut board: BoardStateView) -> BoardStateView {
+        for player in &mut board.players {
+            player.last_seen = self.last_seen_millis(&player.id);
+        }
+        board
+    }
+
+    pub fn view_as(&self, viewer: &Viewer) -> Option<GameView> {
+        match viewer {
+            Viewer::Player(id) => self.view(id),
+            Viewer::Spectator(mode) => Some(self.spectator_view(*mode)),
+        }
+    }
+
+    /// The view for a client asking outside of the broadcasts. Omniscient
+    /// spectators only see hidden information through their delayed
+    /// broadcasts, so they get the public view until the game is over.
+    pub fn requested_view(&self, viewer: &Viewer) -> Option<GameView> {
+        match viewer {
+            Viewer::Spectator(SpectatorMode::Omniscient) if !self.is_over() => {
+                Some(self.spectator_view(SpectatorMode::Public))
+            }
+            _ => self.view_as(viewer),
+        }
+    }
+
+    pub fn may_spectate(&self, mode: SpectatorMode) -> bool {
+        match mode {
+            SpectatorMode::Public => true,
+            // Without a delay, omniscient spectators could tell players the roles.
+            SpectatorMode::Omniscient => {
+                (self.cfg.omniscient_spectators && self.cfg.spectator_delay > 0)
+                    || matches!(self.state, DynamicState::GameOver { .. })
+            }
+        }
+    }
 }
 
 impl DynamicState {
-    pub fn view(&self, of: &Player) -> StateView {
+    /// `sees_cards` controls whether cards revealed only to the president
+    /// are part of the view.
+    pub fn view(&self, sees_cards: bool, deadline: Option<u64>) -> StateView {
         match self {
             Self::Uninit => StateView::Uninit,
             Self::ChooseChancellor { options } => StateView::ChooseChancellor {
                 options: options.clone(),
+                deadline,
             },
             Self::VoteChancellor {
                 chancellor: canidate,
                 ..
             } => StateView::VoteChancellor {
                 canidate: canidate.clone(),
+                deadline,
             },
             Self::PresidentChooseLaws { chancellor, .. } => StateView::PresidentChooseLaws {
                 chancellor: chancellor.clone(),
+                deadline,
             },
             Self::ChancellorChooseLaws {
                 chancellor,
@@ -106,32 +222,48 @@ impl DynamicState {
             } => StateView::ChancellorChooseLaws {
                 chancellor: chancellor.clone(),
                 can_ask_veto: *can_ask_veto,
+                deadline,
             },
             Self::AskVeto { chancellor, .. } => StateView::AskVeto {
                 chancellor: chancellor.clone(),
+                deadline,
             },
 
             Self::ExecutiveAction { chancellor, action } => StateView::ExecutiveAction {
                 chancellor: chancellor.clone(),
-                action: action.clone(),
+                action: match action {
+                    ExecutiveActionTask::RevealNextCards(_) if !sees_cards => {
+                        ExecutiveActionTask::RevealNextCards(Vec::new())
+                    }
+                    action => action.clone(),
+                },
+                deadline,
+            },
+            Self::GameOver { winner, reason } => StateView::GameOver {
+                winner: *winner,
+                reason: *reason,
             },
         }
     }
 }
 
 impl BoardState {
-    pub fn view(&self, of: &Player) -> BoardStateView {
+    /// The board as seen by `of`, or by a spectator if `of` is `None`.
+    pub fn view(&self, of: Option<&Player>, reveal_roles: bool) -> BoardStateView {
         let mut view = BoardStateView {
             players: self
                 .players
                 .iter()
-                .map(|p| p.view(of, self.players.len() < 7))
+                .map(|p| {
+                    let is_host = self.host.as_ref() == Some(&p.id);
+                    p.view(of, self.players.len() < 7, reveal_roles, is_host)
+                })
                 .collect(),
 
-            draw_pile: self.draw_pile.len(),
-            discard_pile: self.discard_pile.len(),
+            draw_pile: self.deck.draw_pile(),
+            discard_pile: self.deck.discard_pile(),
 
-            executive_actions: self.executive_actions,
+            executive_actions: self.executive_actions.clone(),
             voting_result: self.voting_result.clone(),
 
             passed_fasho_laws: self.passed_fasho_laws,
@@ -143,12 +275,16 @@ impl BoardState {
             previous_chancellor: self.previous_chancellor.clone(),
 
             current_president: self.current_president.clone(),
-            next_president_by_rules: self.next_president_by_rules.clone(),
+            next_president_by_rules: (self.rotation != self.current_president)
+                .then(|| self.next_in_rotation()),
             history: self.history.clone(),
+            paused: self.paused,
         };
-        if let Some((knower, target, target_faction)) = &self.revealed_factions {
-            if *knower == of.id {
-                let view = view.players.iter_mut().find(|p| &p.id == target).unwrap();
+        for (knower, target, target_faction) in &self.investigations {
+            if of.is_none_or(|of| of.id != *knower) {
+                continue;
+            }
+            if let Some(view) = view.players.iter_mut().find(|p| &p.id == target) {
                 view.faction = Some(*target_faction);
             }
         }
@@ -157,16 +293,29 @@ impl BoardState {
 }
 
 impl Player {
-    pub fn view(&self, of: &Player, less_than_seven: bool) -> PlayerView {
-        let is_self = of.id == self.id;
-        let alt_knowledge = of.role == Role::FashoHitler && less_than_seven;
+    pub fn view(
+        &self,
+        of: Option<&Player>,
+        less_than_seven: bool,
+        reveal_roles: bool,
+        is_host: bool,
+    ) -> PlayerView {
+        let knows = reveal_roles
+            || of.is_some_and(|of| {
+                let is_self = of.id == self.id;
+                let alt_knowledge = of.role == Role::FashoHitler && less_than_seven;
+                is_self || alt_knowledge || of.role == Role::Fasho
+            });
         PlayerView {
             id: self.id.clone(),
             user: self.user.clone(),
             alive: self.alive,
-            role: (is_self || alt_knowledge || of.role == Role::Fasho).then_some(self.role),
-            faction: (is_self || alt_knowledge || of.role == Role::Fasho)
-                .then_some(self.role.faction()),
+            role: knows.then_some(self.role),
+            faction: knows.then_some(self.role.faction()),
+            bot: self.bot,
+            host: is_host,
+            connected: self.connected,
+            last_seen: None,
         }
     }
 }
@@ -0,0 +1,151 @@
+use std::{error::Error, fmt};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// One change to a JSON document, in the format of a JSON patch (RFC 6902).
+/// Paths are JSON pointers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOp {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+}
+
+/// Reasons why a patch does not apply to a document.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum PatchError {
+    NoSuchPath(String),
+}
+
+impl fmt::Display for PatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchPath(path) => write!(f, "the document has no path {path:?}"),
+        }
+    }
+}
+
+impl Error for PatchError {}
+
+/// The changes that turn `from` into `to`. Arrays are compared element by
+/// element, so that appending to a list, e.g. the history, only adds the new
+/// elements to the patch.
+pub fn diff(from: &Value, to: &Value) -> Vec<PatchOp> {
+    let mut patch = Vec::new();
+    diff_at(&mut String::new(), from, to, &mut patch);
+    patch
+}
+
+fn diff_at(path: &mut String, from: &Value, to: &Value, patch: &mut Vec<PatchOp>) {
+    match (from, to) {
+        (Value::Object(from), Value::Object(to)) => {
+            for key in from.keys().filter(|key| !to.contains_key(*key)) {
+                patch.push(PatchOp::Remove {
+                    path: child(path, key),
+                });
+            }
+            for (key, value) in to {
+                let len = path.len();
+                path.push_str(&child("", key));
+                match from.get(key) {
+                    Some(previous) => diff_at(path, previous, value, patch),
+                    None => patch.push(PatchOp::Add {
+                        path: path.clone(),
+                        value: value.clone(),
+                    }),
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(from), Value::Array(to)) => {
+            for (i, (previous, value)) in from.iter().zip(to).enumerate() {
+                let len = path.len();
+                path.push_str(&format!("/{i}"));
+                diff_at(path, previous, value, patch);
+                path.truncate(len);
+            }
+            for (i, value) in to.iter().enumerate().skip(from.len()) {
+                patch.push(PatchOp::Add {
+                    path: format!("{path}/{i}"),
+                    value: value.clone(),
+                });
+            }
+            for i in (to.len()..from.len()).rev() {
+                patch.push(PatchOp::Remove {
+                    path: format!("{path}/{i}"),
+                });
+            }
+        }
+        _ if from == to => {}
+        _ => patch.push(PatchOp::Replace {
+            path: path.clone(),
+            value: to.clone(),
+        }),
+    }
+}
+
+/// The path of `key` in the object at `path`, escaped as a JSON pointer.
+fn child(path: &str, key: &str) -> String {
+    format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"))
+}
+
+/// Applies `patch` to `doc`. On error, `doc` may be partially patched.
+pub fn apply(doc: &mut Value, patch: &[PatchOp]) -> Result<(), PatchError> {
+    for op in patch {
+        match op {
+            PatchOp::Replace { path, value } => {
+                *doc.pointer_mut(path)
+                    .ok_or_else(|| PatchError::NoSuchPath(path.clone()))? = value.clone();
+            }
+            PatchOp::Add { path, value } => {
+                let (parent, key) = split(path)?;
+                match doc.pointer_mut(parent) {
+                    Some(Value::Object(object)) => {
+                        object.insert(key, value.clone());
+                    }
+                    Some(Value::Array(array)) => {
+                        let index = if key == "-" {
+                            Some(array.len())
+                        } else {
+                            key.parse().ok()
+                        };
+                        match index {
+                            Some(index) if index <= array.len() => {
+                                array.insert(index, value.clone())
+                            }
+                            _ => return Err(PatchError::NoSuchPath(path.clone())),
+                        }
+                    }
+                    _ => return Err(PatchError::NoSuchPath(path.clone())),
+                }
+            }
+            PatchOp::Remove { path } => {
+                let (parent, key) = split(path)?;
+                let removed = match doc.pointer_mut(parent) {
+                    Some(Value::Object(object)) => object.remove(&key),
+                    Some(Value::Array(array)) => key
+                        .parse::<usize>()
+                        .ok()
+                        .filter(|index| *index < array.len())
+                        .map(|index| array.remove(index)),
+                    _ => None,
+                };
+                if removed.is_none() {
+                    return Err(PatchError::NoSuchPath(path.clone()));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Splits a JSON pointer into the pointer of the parent and the unescaped last key.
+fn split(path: &str) -> Result<(&str, String), PatchError> {
+    let (parent, key) = path
+        .rsplit_once('/')
+        .ok_or_else(|| PatchError::NoSuchPath(path.to_string()))?;
+    Ok((parent, key.replace("~1", "/").replace("~0", "~")))
+}
@@ -1,35 +1,76 @@
+pub mod bot;
 mod core;
+mod diff;
+mod lobby;
+mod store;
 pub use core::*;
-use std::{error::Error, net::SocketAddr, sync::Arc};
+pub use diff::*;
+pub use lobby::*;
+use std::{
+    collections::VecDeque,
+    error::Error,
+    io, iter,
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+pub use store::*;
 
 use axum::{
+    body::Bytes,
     extract::{
         ws::{Message, WebSocket},
-        ConnectInfo, State, WebSocketUpgrade,
+        ConnectInfo, Path, State, WebSocketUpgrade,
     },
+    http::StatusCode,
     response::IntoResponse,
-    routing::get,
-    Router,
+    routing::{delete, get, post},
+    Json, Router,
+};
+use axum_extra::{
+    headers::{authorization::Bearer, Authorization, UserAgent},
+    TypedHeader,
 };
-use axum_extra::{headers::UserAgent, TypedHeader};
 use futures_util::{
     stream::{SplitSink, SplitStream},
     SinkExt, StreamExt,
 };
 use rand::random;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tokio::{
     net::TcpListener,
-    sync::{Mutex, RwLock},
+    sync::{mpsc, Mutex, RwLock},
+    task::JoinHandle,
+    time::Instant,
 };
 use tower_http::services::ServeDir;
 
+type WsSink = Arc<Mutex<SplitSink<WebSocket, Message>>>;
+
+static NEXT_CONNECTION: AtomicU64 = AtomicU64::new(0);
+
+/// States a client may leave unacknowledged before it is sent the full state again.
+const MAX_UNACKED: usize = 32;
+
 pub async fn launch() -> Result<(), Box<dyn Error>> {
-    let static_file_service = ServeDir::new("frontend/dist").append_index_html_on_directories(true);
-    let app = Router::new()
-        .fallback_service(static_file_service)
-        .route("/ws", get(handle_ws_upgrade))
-        .with_state(Arc::new(RwLock::new(GameState::new(Vec::new()))));
+    let data_dir = std::env::var("DATA_DIR").unwrap_or("data".to_string());
+    let lobby = Arc::new(Lobby::with_store(Store::new(data_dir)?));
+
+    let restored = lobby.restore().await?;
+    tracing::info!("restored {restored} games");
+
+    if lobby.get(DEFAULT_GAME).await.is_err() {
+        lobby
+            .create(Config {
+                id: DEFAULT_GAME.to_string(),
+                ..Config::default()
+            })
+            .await?;
+    }
 
     let port = std::env::var("PORT")
         .unwrap_or("8000".to_string())
@@ -37,19 +78,124 @@ pub async fn launch() -> Result<(), Box<dyn Error>> {
         .unwrap();
 
     let listener = TcpListener::bind(("0.0.0.0", port)).await?;
+    serve(listener, lobby).await?;
+    Ok(())
+}
+
+/// Serves the lobby's games and the frontend on `listener`.
+pub async fn serve(listener: TcpListener, lobby: Arc<Lobby>) -> io::Result<()> {
+    let static_file_service = ServeDir::new("frontend/dist").append_index_html_on_directories(true);
+    let app = Router::new()
+        .fallback_service(static_file_service)
+        .route("/ws", get(handle_ws_upgrade))
+        .route("/games", get(handle_list_games).post(handle_create_game))
+        .route("/games/:id", delete(handle_close_game))
+        .route("/games/:id/log", get(handle_game_log))
+        .route("/games/:id/bots", post(handle_add_bot))
+        .with_state(lobby);
+
     axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
-    .await?;
-    Ok(())
+    .await
+}
+
+async fn handle_list_games(State(lobby): State<Arc<Lobby>>) -> Json<Vec<GameInfo>> {
+    Json(lobby.list().await)
+}
+
+/// Creates a game with the config in the request body, or the default config
+/// if the body is empty.
+async fn handle_create_game(State(lobby): State<Arc<Lobby>>, body: Bytes) -> impl IntoResponse {
+    let cfg = match Config::from_body(&body) {
+        Ok(cfg) => cfg,
+        Err(e) => return (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response(),
+    };
+    match lobby.create(cfg).await {
+        Ok(info) => (StatusCode::CREATED, Json(info)).into_response(),
+        Err(e @ (LobbyError::InvalidRules(_) | LobbyError::InvalidId(_))) => {
+            (StatusCode::UNPROCESSABLE_ENTITY, Json(e)).into_response()
+        }
+        Err(e) => (StatusCode::CONFLICT, Json(e)).into_response(),
+    }
+}
+
+/// Closes a game. Only the host may do so, authenticated with the host
+/// password or their access key as bearer token.
+async fn handle_close_game(
+    State(lobby): State<Arc<Lobby>>,
+    Path(id): Path<GameId>,
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
+) -> impl IntoResponse {
+    let game = match lobby.get(&id).await {
+        Ok(game) => game,
+        Err(e) => return (StatusCode::NOT_FOUND, Json(e)).into_response(),
+    };
+    if let Err(e) = authorize_host(&game, auth).await {
+        return (StatusCode::FORBIDDEN, Json(e)).into_response();
+    }
+    match lobby.close(&id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::NOT_FOUND, Json(e)).into_response(),
+    }
+}
+
+/// Seats a bot. Only the host may do so, authenticated like
+/// [`handle_close_game`].
+async fn handle_add_bot(
+    State(lobby): State<Arc<Lobby>>,
+    Path(id): Path<GameId>,
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
+) -> impl IntoResponse {
+    let game = match lobby.get(&id).await {
+        Ok(game) => game,
+        Err(e) => return (StatusCode::NOT_FOUND, Json(e)).into_response(),
+    };
+    if let Err(e) = authorize_host(&game, auth).await {
+        return (StatusCode::FORBIDDEN, Json(e)).into_response();
+    }
+    let result = game.write().await.add_bot(None);
+    match result {
+        Ok(player) => (StatusCode::CREATED, Json(player.id)).into_response(),
+        Err(e) => (StatusCode::CONFLICT, Json(e)).into_response(),
+    }
+}
+
+/// Checks the bearer token of a request, see [`GameState::authorize_host_credential`].
+async fn authorize_host(
+    game: &RwLock<GameState>,
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
+) -> Result<(), ActionError> {
+    let Some(TypedHeader(Authorization(bearer))) = auth else {
+        return Err(ActionError::NotHost);
+    };
+    game.read().await.authorize_host_credential(bearer.token())
+}
+
+/// Serves the action log of a game for debugging. It is only available when no
+/// round is running, and reveals the seeds of the rounds, which reveal all
+/// roles, only once the game is over.
+async fn handle_game_log(
+    State(lobby): State<Arc<Lobby>>,
+    Path(id): Path<GameId>,
+) -> impl IntoResponse {
+    let game = match lobby.get(&id).await {
+        Ok(game) => game,
+        Err(e) => return (StatusCode::NOT_FOUND, Json(e)).into_response(),
+    };
+    let game = game.read().await;
+    if game.is_running() {
+        return StatusCode::CONFLICT.into_response();
+    }
+    Json(game.log().redacted(game.is_over())).into_response()
 }
 
 async fn handle_ws_upgrade(
     ws: WebSocketUpgrade,
     user_agent: Option<TypedHeader<UserAgent>>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-    State(game_state): State<Arc<RwLock<GameState>>>,
+    State(lobby): State<Arc<Lobby>>,
 ) -> impl IntoResponse {
     let user_agent = if let Some(TypedHeader(user_agent)) = user_agent {
         user_agent.to_string()
@@ -59,7 +205,7 @@ async fn handle_ws_upgrade(
 
     tracing::info!("Connected {} at {}", user_agent, addr);
     ws.on_upgrade(move |socket| async move {
-        match handle_ws(socket, addr, game_state).await {
+        match handle_ws(socket, addr, lobby).await {
             Ok(()) => (),
             Err(e) => tracing::error!("> {e} <"),
         }
@@ -69,7 +215,7 @@ async fn handle_ws_upgrade(
 async fn handle_ws(
     mut ws: WebSocket,
     client: SocketAddr,
-    game_state: Arc<RwLock<GameState>>,
+    lobby: Arc<Lobby>,
 ) -> Result<(), Box<dyn Error>> {
     let cookie = random::<u128>();
     ws.send(Message::Ping(cookie.to_be_bytes().to_vec()))
@@ -88,28 +234,98 @@ async fn handle_ws(
     let (tx, rx) = ws.split();
     let tx = Arc::new(Mutex::new(tx));
 
-    let user = Arc::new(RwLock::new(String::new()));
-
-    tokio::spawn(handle_ws_rx(
-        rx,
-        tx.clone(),
-        user.clone(),
-        game_state.clone(),
-    ));
-    tokio::spawn(handle_ws_tx(tx, user, game_state));
+    tokio::spawn(handle_ws_rx(rx, tx, lobby));
 
     Ok(())
 }
 
-async fn handle_ws_rx(
-    mut rx: SplitStream<WebSocket>,
-    tx: Arc<Mutex<SplitSink<WebSocket, Message>>>,
-    user: Arc<RwLock<PlayerId>>,
-    game_state: Arc<RwLock<GameState>>,
-) {
+/// The game a connection has joined, and as whom.
+struct Session {
+    game_id: GameId,
+    game: Arc<RwLock<GameState>>,
+    viewer: Viewer,
+    connection: ConnectionId,
+    /// Forwards acknowledged versions to the tx task, if the client receives diffs.
+    acks: Option<mpsc::UnboundedSender<u64>>,
+    tx_task: JoinHandle<()>,
+}
+
+impl Session {
+    fn start(
+        lobby: Arc<Lobby>,
+        game_id: GameId,
+        game: Arc<RwLock<GameState>>,
+        viewer: Viewer,
+        connection: ConnectionId,
+        diffs: bool,
+        tx: WsSink,
+    ) -> Self {
+        let (acks, diffs) = if diffs {
+            let (acks, rx) = mpsc::unbounded_channel();
+            (Some(acks), Some(Diffs::new(rx)))
+        } else {
+            (None, None)
+        };
+        let tx_task = tokio::spawn(handle_ws_tx(
+            tx,
+            viewer.clone(),
+            connection,
+            diffs,
+            game_id.clone(),
+            game.clone(),
+            lobby,
+        ));
+        Session {
+            game_id,
+            game,
+            viewer,
+            connection,
+            acks,
+            tx_task,
+        }
+    }
+
+    /// Whether the player of this session has not connected elsewhere since.
+    async fn is_current(&self) -> bool {
+        match &self.viewer {
+            Viewer::Player(user) => self
+                .game
+                .read()
+                .await
+                .is_current_connection(user, self.connection),
+            Viewer::Spectator(_) => true,
+        }
+    }
+
+    /// Checks that the player of this session hosts the game.
+    async fn authorize_host(&self) -> Result<(), ActionError> {
+        match &self.viewer {
+            Viewer::Player(user) => self.game.read().await.authorize_host(user),
+            Viewer::Spectator(_) => Err(ActionError::NotAPlayer),
+        }
+    }
+
+    async fn leave(self) {
+        self.tx_task.abort();
+        if let Viewer::Player(user) = &self.viewer {
+            self.game.write().await.disconnect(user, self.connection);
+        }
+    }
+}
+
+async fn send(tx: &WsSink, msg: &OutgoingMessage) -> Result<(), axum::Error> {
+    tx.lock()
+        .await
+        .send(Message::Text(serde_json::to_string(msg).unwrap()))
+        .await
+}
+
+async fn handle_ws_rx(mut rx: SplitStream<WebSocket>, tx: WsSink, lobby: Arc<Lobby>) {
+    let mut session: Option<Session> = None;
+
     while let Some(msg) = rx.next().await {
         let Ok(msg) = msg else {
-            return;
+            break;
         };
         match msg {
             Message::Ping(_) | Message::Pong(_) => {}
@@ -126,131 +342,570 @@ async fn handle_ws_rx(
                 };
                 match incoming {
                     IncomingMessage::Authenticate(auth) => {
-                        if auth.user.is_some() {
+                        let game_id = auth.game.unwrap_or_else(|| DEFAULT_GAME.to_string());
+                        let game = match lobby.get(&game_id).await {
+                            Ok(game) => game,
+                            Err(e) => {
+                                tracing::warn!("{e}");
+                                let _ = send(&tx, &OutgoingMessage::LobbyError(e)).await;
+                                continue;
+                            }
+                        };
+
+                        if let Some(mode) = auth.spectate {
+                            if !game.read().await.may_spectate(mode) {
+                                let msg =
+                                    OutgoingMessage::LobbyError(LobbyError::SpectatingForbidden);
+                                let _ = send(&tx, &msg).await;
+                                continue;
+                            }
+
+                            if let Some(previous) = session.take() {
+                                previous.leave().await;
+                            }
+                            if send(&tx, &OutgoingMessage::Spectating(mode)).await.is_err() {
+                                break;
+                            }
+
+                            tracing::info!("spectator joined game {game_id:?} in mode {mode:?}");
+                            session = Some(Session::start(
+                                lobby.clone(),
+                                game_id,
+                                game,
+                                Viewer::Spectator(mode),
+                                NEXT_CONNECTION.fetch_add(1, Ordering::Relaxed),
+                                auth.diffs,
+                                tx.clone(),
+                            ));
+                            continue;
+                        }
+
+                        let player = if let Some(user) = auth.user {
                             // Register new user
-                            let player = game_state
-                                .write()
-                                .await
-                                .add_new_user(auth.user.unwrap())
-                                .unwrap();
-                            *user.write().await = player.id;
-                            tx.lock()
-                                .await
-                                .send(Message::Text(
-                                    serde_json::to_string(&OutgoingMessage::Authenticated {
-                                        access_key: player.access_key,
-                                    })
-                                    .unwrap(),
-                                ))
-                                .await
-                                .unwrap();
+                            game.write().await.add_new_user(user)
                         } else {
                             // Reconnect user
-                            let Ok(player) = game_state
-                                .write()
+                            game.write()
                                 .await
-                                .join_existing_user(auth.access_key.unwrap())
-                            else {
-                                tracing::warn!("invalid access key");
+                                .join_existing_user(auth.access_key.unwrap_or_default())
+                        };
+                        let player = match player {
+                            Ok(player) => player,
+                            Err(e) => {
+                                tracing::warn!("authentication failed: {e}");
+                                let _ = send(&tx, &OutgoingMessage::Error(e)).await;
                                 continue;
-                            };
-                            *user.write().await = player.id;
-                            tx.lock()
-                                .await
-                                .send(Message::Text(
-                                    serde_json::to_string(&OutgoingMessage::Authenticated {
-                                        access_key: player.access_key,
-                                    })
-                                    .unwrap(),
-                                ))
-                                .await
-                                .unwrap();
+                            }
+                        };
+
+                        // Connect before leaving the previous session, so
+                        // that rejoining as the same player keeps the seat.
+                        let connection = NEXT_CONNECTION.fetch_add(1, Ordering::Relaxed);
+                        game.write().await.connect(&player.id, connection);
+                        if let Some(previous) = session.take() {
+                            previous.leave().await;
+                        }
+
+                        if let Some(password) = auth.host_password {
+                            let result = game.write().await.claim_host(&player.id, &password);
+                            if let Err(e) = result {
+                                tracing::warn!("{:?} failed to claim host: {e}", player.id);
+                                let _ = send(&tx, &OutgoingMessage::Error(e)).await;
+                            }
+                        }
+
+                        if send(
+                            &tx,
+                            &OutgoingMessage::Authenticated {
+                                access_key: player.access_key,
+                            },
+                        )
+                        .await
+                        .is_err()
+                        {
+                            game.write().await.disconnect(&player.id, connection);
+                            break;
+                        }
+
+                        session = Some(Session::start(
+                            lobby.clone(),
+                            game_id,
+                            game,
+                            Viewer::Player(player.id),
+                            connection,
+                            auth.diffs,
+                            tx.clone(),
+                        ));
+                    }
+                    IncomingMessage::Ack(version) => {
+                        if let Some(acks) = session.as_ref().and_then(|s| s.acks.as_ref()) {
+                            let _ = acks.send(version);
                         }
                     }
                     IncomingMessage::GetState => {
-                        let game_state = game_state.read().await;
-                        let id = &user.read().await;
-                        let view = game_state.view(id);
-                        let tasks = game_state.tasks(id);
-                        tx.lock()
+                        let Some(session) = &session else {
+                            continue;
+                        };
+                        let game_state = session.game.read().await;
+                        if let Viewer::Spectator(mode) = session.viewer {
+                            if !game_state.may_spectate(mode) {
+                                let _ = send(
+                                    &tx,
+                                    &OutgoingMessage::LobbyError(LobbyError::SpectatingForbidden),
+                                )
+                                .await;
+                                continue;
+                            }
+                        }
+                        let view = game_state.requested_view(&session.viewer);
+                        let tasks = match &session.viewer {
+                            Viewer::Player(id) => game_state.tasks(id),
+                            Viewer::Spectator(_) => None,
+                        };
+                        let _ = tx
+                            .lock()
                             .await
                             .send(Message::Text(
                                 serde_json::to_string(&(view, tasks)).unwrap(),
                             ))
-                            .await
-                            .unwrap();
+                            .await;
                     }
                     IncomingMessage::Task(task_response) => {
-                        let mut game_state = game_state.write().await;
-                        let id = &user.read().await;
+                        let Some(current) = &session else {
+                            continue;
+                        };
+                        if !current.is_current().await {
+                            session = None;
+                            let _ = send(&tx, &OutgoingMessage::Replaced).await;
+                            continue;
+                        }
+                        let session = current;
+                        let Viewer::Player(user) = &session.viewer else {
+                            let _ =
+                                send(&tx, &OutgoingMessage::Error(ActionError::NotAPlayer)).await;
+                            continue;
+                        };
+                        let mut game_state = session.game.write().await;
 
                         tracing::info!("executing task {task_response:?}");
-                        game_state.on_action(id.to_string(), task_response);
+                        if let Err(e) = game_state.on_action(user.clone(), task_response) {
+                            tracing::warn!("rejected task of {user:?}: {e}");
+                            drop(game_state);
+                            let _ = send(&tx, &OutgoingMessage::Error(e)).await;
+                        }
+                    }
+                    IncomingMessage::Host(action) => {
+                        let Some(current) = &session else {
+                            continue;
+                        };
+                        if !current.is_current().await {
+                            session = None;
+                            let _ = send(&tx, &OutgoingMessage::Replaced).await;
+                            continue;
+                        }
+                        let session = current;
+                        let Viewer::Player(user) = &session.viewer else {
+                            let _ =
+                                send(&tx, &OutgoingMessage::Error(ActionError::NotAPlayer)).await;
+                            continue;
+                        };
+
+                        tracing::info!("executing host action {action:?}");
+                        let result = session
+                            .game
+                            .write()
+                            .await
+                            .on_host_action(user.clone(), action);
+                        if let Err(e) = result {
+                            tracing::warn!("rejected host action of {user:?}: {e}");
+                            let _ = send(&tx, &OutgoingMessage::Error(e)).await;
+                        }
+                    }
+                    IncomingMessage::Chat { channel, text } => {
+                        let Some(current) = &session else {
+                            continue;
+                        };
+                        if !current.is_current().await {
+                            session = None;
+                            let _ = send(&tx, &OutgoingMessage::Replaced).await;
+                            continue;
+                        }
+                        let Viewer::Player(user) = &current.viewer else {
+                            let _ =
+                                send(&tx, &OutgoingMessage::Error(ActionError::NotAPlayer)).await;
+                            continue;
+                        };
+                        let result =
+                            current
+                                .game
+                                .write()
+                                .await
+                                .on_chat(user.clone(), channel, text);
+                        if let Err(e) = result {
+                            tracing::warn!("rejected chat message of {user:?}: {e}");
+                            let _ = send(&tx, &OutgoingMessage::Error(e)).await;
+                        }
+                    }
+                    IncomingMessage::EditUser(user) => {
+                        let Some(current) = &session else {
+                            continue;
+                        };
+                        if !current.is_current().await {
+                            session = None;
+                            let _ = send(&tx, &OutgoingMessage::Replaced).await;
+                            continue;
+                        }
+                        let session = current;
+                        let Viewer::Player(player) = &session.viewer else {
+                            let _ =
+                                send(&tx, &OutgoingMessage::Error(ActionError::NotAPlayer)).await;
+                            continue;
+                        };
+                        let result = session.game.write().await.edit_user(player.clone(), user);
+                        if let Err(e) = result {
+                            tracing::warn!("rejected edit of {player:?}: {e}");
+                            let _ = send(&tx, &OutgoingMessage::Error(e)).await;
+                        }
+                    }
+                    IncomingMessage::Leave => {
+                        let Some(current) = session.take() else {
+                            continue;
+                        };
+                        // A replaced tab does not free the seat of the current one.
+                        let player = match &current.viewer {
+                            Viewer::Player(player) if current.is_current().await => {
+                                Some(player.clone())
+                            }
+                            _ => None,
+                        };
+                        let game = current.game.clone();
+                        current.leave().await;
+                        if let Some(player) = player {
+                            game.write().await.remove_player(&player);
+                        }
+                    }
+                    IncomingMessage::AddBot(name) => {
+                        let Some(session) = &session else {
+                            continue;
+                        };
+                        let result = match session.authorize_host().await {
+                            Ok(()) => session.game.write().await.add_bot(name),
+                            Err(e) => Err(e),
+                        };
+                        if let Err(e) = result {
+                            tracing::warn!("failed to add bot: {e}");
+                            let _ = send(&tx, &OutgoingMessage::Error(e)).await;
+                        }
+                    }
+                    IncomingMessage::ListGames => {
+                        let _ = send(&tx, &OutgoingMessage::Games(lobby.list().await)).await;
+                    }
+                    IncomingMessage::CreateGame => {
+                        let msg = match lobby.create(Config::default()).await {
+                            Ok(info) => OutgoingMessage::GameCreated(info),
+                            Err(e) => OutgoingMessage::LobbyError(e),
+                        };
+                        let _ = send(&tx, &msg).await;
+                    }
+                    IncomingMessage::CloseGame(id) => {
+                        // Only the host, in the game's own session, may close it.
+                        let authorized = match &session {
+                            Some(session) if session.game_id == id => {
+                                session.authorize_host().await
+                            }
+                            _ => Err(ActionError::NotHost),
+                        };
+                        let result = match authorized {
+                            Ok(()) => lobby.close(&id).await.map_err(OutgoingMessage::LobbyError),
+                            Err(e) => Err(OutgoingMessage::Error(e)),
+                        };
+                        if let Err(msg) = result {
+                            let _ = send(&tx, &msg).await;
+                        }
                     }
-                    _ => todo!(),
                 }
             }
-            Message::Close(close_frame) => {
+            Message::Close(_) => {
                 tracing::info!("Closing socket");
-                game_state.write().await.remove_player(&*user.read().await);
+                break;
             }
         }
     }
+
+    if let Some(session) = session {
+        tracing::debug!("leaving game {:?}", session.game_id);
+        session.leave().await;
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum IncomingMessage {
     Task(TaskAction),
+    Host(HostAction),
     Authenticate(AuthenticateMessage),
+    /// Changes the name, avatar or colour of the player before the game starts.
+    EditUser(User),
+    /// Gives up the seat in the lobby. Merely disconnecting keeps it.
+    Leave,
+    /// Posts a chat message, see [`ChatRules`].
+    Chat {
+        #[serde(default)]
+        channel: ChatChannel,
+        text: String,
+    },
+    /// Confirms that the client holds this version of the state, see
+    /// [`AuthenticateMessage::diffs`]. Acknowledging a version the server
+    /// did not expect makes it send the full state again.
+    Ack(u64),
     GetState,
+    /// Seats a bot, named as given or generically. Only the host may do so.
+    AddBot(Option<String>),
+    ListGames,
+    CreateGame,
+    /// Closes the game of the session. Only the host may do so.
+    CloseGame(GameId),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AuthenticateMessage {
+    #[serde(default)]
+    pub game: Option<GameId>,
     pub user: Option<User>,
     pub access_key: Option<String>,
+    /// Join as a spectator instead of as a player.
+    #[serde(default)]
+    pub spectate: Option<SpectatorMode>,
+    /// Claims the host role, see [`Config::host_password`].
+    #[serde(default)]
+    pub host_password: Option<String>,
+    /// Receive [`OutgoingMessage::Diff`]s instead of the full state on every
+    /// change. Every state has to be acknowledged with [`IncomingMessage::Ack`].
+    #[serde(default)]
+    pub diffs: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type", content = "value")]
 pub enum OutgoingMessage {
     State {
-        game_state: GameView,
+        /// The version of the game this state belongs to, see [`GameState::version`].
+        version: u64,
+        game_state: Box<GameView>,
         task: Option<Task>,
     },
+    /// The changes to the `value` of the previous [`OutgoingMessage::State`]
+    /// or diff, which had version `base`.
+    Diff {
+        base: u64,
+        version: u64,
+        patch: Vec<PatchOp>,
+    },
     Authenticated {
         access_key: String,
     },
+    Spectating(SpectatorMode),
+    Games(Vec<GameInfo>),
+    GameCreated(GameInfo),
+    GameClosed(GameId),
+    /// A chat message this connection may read. On joining a game, the
+    /// recent messages are sent as well.
+    Chat(ChatMessage),
+    /// The host removed this player from the game.
+    Kicked,
+    /// The player connected to the game elsewhere, e.g. in another tab.
+    /// This connection no longer plays for them.
+    Replaced,
+    LobbyError(LobbyError),
+    Error(ActionError),
 }
 
 async fn handle_ws_tx(
-    tx: Arc<Mutex<SplitSink<WebSocket, Message>>>,
-    user: Arc<RwLock<PlayerId>>,
+    tx: WsSink,
+    viewer: Viewer,
+    connection: ConnectionId,
+    mut diffs: Option<Diffs>,
+    game_id: GameId,
     game_state: Arc<RwLock<GameState>>,
+    lobby: Arc<Lobby>,
 ) {
-    let notify = game_state.read().await.notify();
-    loop {
-        notify.notified().await;
-        let id = &user.read().await;
+    let (mut updates, delay) = {
         let game_state = game_state.read().await;
-
-        let Some(view) = game_state.view(id) else {
-            continue;
+        let delay = match viewer {
+            Viewer::Spectator(SpectatorMode::Omniscient) => game_state.config().spectator_delay,
+            _ => 0,
         };
+        (game_state.subscribe(), Duration::from_secs(delay))
+    };
+    let delayed = (!delay.is_zero()).then(|| delayed_sender(tx.clone(), delay));
+    let mut last_chat = None;
 
-        let msg = OutgoingMessage::State {
-            game_state: view,
-            task: game_state.tasks(id),
+    loop {
+        let is_open = lobby
+            .get(&game_id)
+            .await
+            .is_ok_and(|game| Arc::ptr_eq(&game, &game_state));
+        let (msg, chat) = if is_open {
+            let game_state = game_state.read().await;
+            // Changes need the write lock, so the version matches the view.
+            let version = *updates.borrow_and_update();
+            let replaced = match &viewer {
+                Viewer::Player(id) => !game_state.is_current_connection(id, connection),
+                Viewer::Spectator(_) => false,
+            };
+            // Spectators who joined a finished game may not watch the next one.
+            let forbidden = match viewer {
+                Viewer::Spectator(mode) => !game_state.may_spectate(mode),
+                Viewer::Player(_) => false,
+            };
+            let chat = game_state.chat_for(&viewer, last_chat);
+            let msg = match game_state.view_as(&viewer) {
+                Some(_) if forbidden => {
+                    OutgoingMessage::LobbyError(LobbyError::SpectatingForbidden)
+                }
+                Some(_) if replaced => OutgoingMessage::Replaced,
+                Some(view) => OutgoingMessage::State {
+                    version,
+                    game_state: Box::new(view),
+                    task: match &viewer {
+                        Viewer::Player(id) => game_state.tasks(id),
+                        Viewer::Spectator(_) => None,
+                    },
+                },
+                None => OutgoingMessage::Kicked,
+            };
+            (msg, chat)
+        } else {
+            (OutgoingMessage::GameClosed(game_id.clone()), Vec::new())
+        };
+        let last = !matches!(msg, OutgoingMessage::State { .. });
+        let close = matches!(msg, OutgoingMessage::Replaced | OutgoingMessage::Kicked);
+        let msg = match &mut diffs {
+            Some(diffs) => diffs.encode(msg),
+            None => msg,
         };
+        let chat = if last { Vec::new() } else { chat };
+        last_chat = chat.last().map(|c| c.id).or(last_chat);
 
-        if let Err(e) = tx
-            .lock()
-            .await
-            .send(Message::Text(serde_json::to_string(&msg).unwrap()))
-            .await
-        {
-            tracing::error!("closing tx socket: {e}");
+        for msg in iter::once(msg).chain(chat.into_iter().map(OutgoingMessage::Chat)) {
+            if let Some(delayed) = &delayed {
+                if delayed.send((Instant::now(), msg)).is_err() {
+                    return;
+                }
+            } else if let Err(e) = send(&tx, &msg).await {
+                tracing::error!("closing tx socket: {e}");
+                return;
+            }
+        }
+        if close {
+            let _ = tx.lock().await.send(Message::Close(None)).await;
+        }
+        if last {
             break;
         }
+
+        // Resolves at once if the game changed while the message was sent.
+        loop {
+            tokio::select! {
+                changed = updates.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                    break;
+                }
+                Some(version) = next_ack(&mut diffs) => {
+                    if !diffs.as_mut().is_some_and(|diffs| diffs.ack(version)) {
+                        tracing::debug!("client fell behind at version {version}");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// The states sent to a client that receives diffs.
+struct Diffs {
+    acks: mpsc::UnboundedReceiver<u64>,
+    /// The last state sent, which the next diff is based on.
+    sent: Option<(u64, Value)>,
+    /// Versions sent, but not acknowledged yet.
+    unacked: VecDeque<u64>,
+    /// The version of the last full state. Older acknowledgements were sent
+    /// before the client received it, and are ignored.
+    resynced: u64,
+}
+
+impl Diffs {
+    fn new(acks: mpsc::UnboundedReceiver<u64>) -> Self {
+        Diffs {
+            acks,
+            sent: None,
+            unacked: VecDeque::new(),
+            resynced: 0,
+        }
     }
+
+    /// Turns a full state into a diff against the previously sent one, unless
+    /// the client has to be sent the full state again.
+    fn encode(&mut self, msg: OutgoingMessage) -> OutgoingMessage {
+        let OutgoingMessage::State { version, .. } = msg else {
+            return msg;
+        };
+        let mut doc = serde_json::to_value(&msg).unwrap()["value"].take();
+
+        let msg = match &self.sent {
+            Some((base, sent)) if self.unacked.len() < MAX_UNACKED => OutgoingMessage::Diff {
+                base: *base,
+                version,
+                patch: diff(sent, &doc),
+            },
+            _ => {
+                self.unacked.clear();
+                self.resynced = version;
+                msg
+            }
+        };
+        self.sent = Some((version, doc.take()));
+        self.unacked.push_back(version);
+        msg
+    }
+
+    /// Records that the client holds `version`. Returns `false` if the client
+    /// has to be sent the full state again.
+    fn ack(&mut self, version: u64) -> bool {
+        if let Some(acked) = self.unacked.iter().position(|v| *v == version) {
+            self.unacked.drain(..=acked);
+            return true;
+        }
+        if version < self.resynced {
+            return true;
+        }
+        self.sent = None;
+        false
+    }
+}
+
+async fn next_ack(diffs: &mut Option<Diffs>) -> Option<u64> {
+    match diffs {
+        Some(diffs) => diffs.acks.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Forwards queued messages to the socket, each `delay` after it was queued.
+fn delayed_sender(
+    tx: WsSink,
+    delay: Duration,
+) -> mpsc::UnboundedSender<(Instant, OutgoingMessage)> {
+    let (queue, mut rx) = mpsc::unbounded_channel::<(Instant, OutgoingMessage)>();
+    tokio::spawn(async move {
+        while let Some((queued, msg)) = rx.recv().await {
+            tokio::time::sleep_until(queued + delay).await;
+            if let Err(e) = send(&tx, &msg).await {
+                tracing::error!("closing delayed tx socket: {e}");
+                break;
+            }
+        }
+    });
+    queue
 }
@@ -0,0 +1,298 @@
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt, io,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use rand::{distributions::Alphanumeric, rngs::StdRng, thread_rng, Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use tokio::{sync::RwLock, task::JoinHandle};
+
+use crate::{bot, Config, GameState, RulesError, Store};
+
+pub type GameId = String;
+
+/// The id of the game that clients join, if they do not ask for a specific one.
+pub const DEFAULT_GAME: &str = "default";
+/// The maximum length of a game id. Ids may only contain ASCII letters,
+/// digits, `_` and `-`, since they name the game's file in the [`Store`].
+pub const MAX_GAME_ID_LENGTH: usize = 64;
+
+/// Registry of all games (rooms) hosted by this server.
+#[derive(Debug, Default)]
+pub struct Lobby {
+    games: RwLock<HashMap<GameId, Room>>,
+    store: Option<Store>,
+}
+
+#[derive(Debug)]
+struct Room {
+    game: Arc<RwLock<GameState>>,
+    persist: Option<JoinHandle<()>>,
+    timer: JoinHandle<()>,
+    bots: JoinHandle<()>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct GameInfo {
+    pub id: GameId,
+    pub players: usize,
+    pub running: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value")]
+pub enum LobbyError {
+    NoSuchGame(GameId),
+    GameExists(GameId),
+    InvalidId(GameId),
+    SpectatingForbidden,
+    InvalidRules(RulesError),
+}
+
+impl fmt::Display for LobbyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSuchGame(id) => write!(f, "no game with id {id:?}"),
+            Self::GameExists(id) => write!(f, "game with id {id:?} already exists"),
+            Self::InvalidId(id) => write!(f, "{id:?} is not a valid game id"),
+            Self::SpectatingForbidden => write!(f, "this game does not allow such spectators"),
+            Self::InvalidRules(e) => write!(f, "invalid rules: {e}"),
+        }
+    }
+}
+
+impl Error for LobbyError {}
+
+impl GameInfo {
+    pub fn of(game: &GameState) -> GameInfo {
+        GameInfo {
+            id: game.id().to_string(),
+            players: game.player_count(),
+            running: game.is_running(),
+        }
+    }
+}
+
+impl Lobby {
+    pub fn new() -> Lobby {
+        Lobby::default()
+    }
+
+    /// Creates a lobby that snapshots every game to `store`.
+    pub fn with_store(store: Store) -> Lobby {
+        Lobby {
+            games: RwLock::default(),
+            store: Some(store),
+        }
+    }
+
+    /// Reloads all games found in the store. Returns the number of restored games.
+    pub async fn restore(&self) -> io::Result<usize> {
+        let Some(store) = &self.store else {
+            return Ok(0);
+        };
+
+        let mut games = self.games.write().await;
+        let mut count = 0;
+        for snapshot in store.load_all().await? {
+            let game = GameState::restore(snapshot);
+            tracing::info!("restored game {:?}", game.id());
+            games.insert(game.id().to_string(), self.room(game));
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    fn room(&self, game: GameState) -> Room {
+        let game = Arc::new(RwLock::new(game));
+        let persist = self
+            .store
+            .clone()
+            .map(|store| tokio::spawn(persist(store, game.clone())));
+        let timer = tokio::spawn(enforce_deadlines(game.clone()));
+        let bots = tokio::spawn(drive_bots(game.clone()));
+        Room {
+            game,
+            persist,
+            timer,
+            bots,
+        }
+    }
+
+    /// Creates a new game. If `cfg.id` is empty a random id is assigned.
+    pub async fn create(&self, mut cfg: Config) -> Result<GameInfo, LobbyError> {
+        cfg.rules.validate().map_err(LobbyError::InvalidRules)?;
+        let mut games = self.games.write().await;
+        if cfg.id.is_empty() {
+            cfg.id = loop {
+                let id = random_game_id();
+                if !games.contains_key(&id) {
+                    break id;
+                }
+            };
+        } else if !is_valid_id(&cfg.id) {
+            return Err(LobbyError::InvalidId(cfg.id));
+        } else if games.contains_key(&cfg.id) {
+            return Err(LobbyError::GameExists(cfg.id));
+        }
+
+        let game = GameState::new(cfg);
+        let info = GameInfo::of(&game);
+        tracing::info!("created game {:?}", info.id);
+        games.insert(info.id.clone(), self.room(game));
+        Ok(info)
+    }
+
+    pub async fn get(&self, id: &str) -> Result<Arc<RwLock<GameState>>, LobbyError> {
+        self.games
+            .read()
+            .await
+            .get(id)
+            .map(|room| room.game.clone())
+            .ok_or_else(|| LobbyError::NoSuchGame(id.to_string()))
+    }
+
+    pub async fn list(&self) -> Vec<GameInfo> {
+        let games = self
+            .games
+            .read()
+            .await
+            .values()
+            .map(|room| room.game.clone())
+            .collect::<Vec<_>>();
+        let mut infos = Vec::with_capacity(games.len());
+        for game in games {
+            infos.push(GameInfo::of(&*game.read().await));
+        }
+        infos.sort_by(|a, b| a.id.cmp(&b.id));
+        infos
+    }
+
+    /// Removes a game from the registry, waking all connections
+    /// subscribed to it so they can shut down.
+    pub async fn close(&self, id: &str) -> Result<(), LobbyError> {
+        let room = self
+            .games
+            .write()
+            .await
+            .remove(id)
+            .ok_or_else(|| LobbyError::NoSuchGame(id.to_string()))?;
+
+        if let Some(persist) = room.persist {
+            persist.abort();
+        }
+        room.timer.abort();
+        room.bots.abort();
+        if let Some(store) = &self.store {
+            if let Err(e) = store.remove(id).await {
+                tracing::error!("failed to remove snapshot of {id:?}: {e}");
+            }
+        }
+
+        tracing::info!("closed game {id:?}");
+        room.game.read().await.changed();
+        Ok(())
+    }
+}
+
+/// Snapshots the game to the store each time it changes. Changes made while
+/// a snapshot is written are saved in a single snapshot afterwards.
+async fn persist(store: Store, game: Arc<RwLock<GameState>>) {
+    let mut updates = game.read().await.subscribe();
+    loop {
+        let snapshot = {
+            let game = game.read().await;
+            updates.borrow_and_update();
+            game.snapshot()
+        };
+        if let Err(e) = store.save(&snapshot).await {
+            tracing::error!("failed to persist game {:?}: {e}", snapshot.id());
+        }
+
+        if updates.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// Applies the default actions whenever a phase of the game times out.
+async fn enforce_deadlines(game: Arc<RwLock<GameState>>) {
+    let mut updates = game.read().await.subscribe();
+    loop {
+        let deadline = {
+            let game = game.read().await;
+            updates.borrow_and_update();
+            game.deadline()
+        };
+        let Some(deadline) = deadline else {
+            if updates.changed().await.is_err() {
+                break;
+            }
+            continue;
+        };
+
+        let remaining = deadline
+            .duration_since(SystemTime::now())
+            .unwrap_or_default();
+        tokio::select! {
+            changed = updates.changed() => {
+                if changed.is_err() {
+                    break;
+                }
+            }
+            _ = tokio::time::sleep(remaining) => {
+                game.write().await.on_timeout(&mut thread_rng());
+            }
+        }
+    }
+}
+
+/// How long bots wait before they act, so humans can follow the game.
+const BOT_DELAY: Duration = Duration::from_millis(1500);
+
+/// Lets the bots of the game act whenever they hold a task.
+async fn drive_bots(game: Arc<RwLock<GameState>>) {
+    let mut updates = game.read().await.subscribe();
+    let mut rng = StdRng::from_entropy();
+    loop {
+        let bots_pending = {
+            let game = game.read().await;
+            updates.borrow_and_update();
+            game.players()
+                .iter()
+                .any(|p| p.bot && game.tasks(&p.id).is_some())
+        };
+        if !bots_pending {
+            if updates.changed().await.is_err() {
+                break;
+            }
+            continue;
+        }
+
+        tokio::time::sleep(BOT_DELAY).await;
+        let mut game = game.write().await;
+        for (player, action) in bot::bot_actions(&game, &mut rng) {
+            if let Err(e) = game.on_action(player.clone(), action) {
+                tracing::error!("bot {player:?} made an invalid move: {e}");
+            }
+        }
+    }
+}
+
+fn random_game_id() -> GameId {
+    thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(6)
+        .map(|c| char::from(c).to_ascii_uppercase())
+        .collect()
+}
+
+fn is_valid_id(id: &str) -> bool {
+    id.len() <= MAX_GAME_ID_LENGTH
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
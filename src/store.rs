@@ -0,0 +1,74 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use tokio::fs;
+
+use crate::GameSnapshot;
+
+/// Stores snapshots of games as JSON files in a local directory,
+/// one file per game.
+#[derive(Debug, Clone)]
+pub struct Store {
+    dir: PathBuf,
+}
+
+impl Store {
+    pub fn new(dir: impl AsRef<Path>) -> io::Result<Store> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)?;
+        Ok(Store { dir })
+    }
+
+    fn path(&self, id: &str) -> PathBuf {
+        let path = self.dir.join(format!("{id}.json"));
+        // The lobby only accepts ids that are plain file names.
+        assert_eq!(
+            path.parent(),
+            Some(self.dir.as_path()),
+            "game id {id:?} escapes the data directory"
+        );
+        path
+    }
+
+    /// Writes the snapshot to disk, replacing any older snapshot of the same game.
+    pub async fn save(&self, snapshot: &GameSnapshot) -> io::Result<()> {
+        let raw = serde_json::to_vec(snapshot).map_err(io::Error::other)?;
+        let path = self.path(snapshot.id());
+
+        // Write to a temporary file first, so that a crash never leaves
+        // a half written snapshot behind.
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, raw).await?;
+        fs::rename(&tmp, &path).await
+    }
+
+    pub async fn remove(&self, id: &str) -> io::Result<()> {
+        match fs::remove_file(self.path(id)).await {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Loads all snapshots in the data directory. Unreadable files are skipped.
+    pub async fn load_all(&self) -> io::Result<Vec<GameSnapshot>> {
+        let mut snapshots = Vec::new();
+        let mut entries = fs::read_dir(&self.dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "json") {
+                continue;
+            }
+
+            let snapshot = fs::read(&path)
+                .await
+                .and_then(|raw| serde_json::from_slice(&raw).map_err(io::Error::other));
+            match snapshot {
+                Ok(snapshot) => snapshots.push(snapshot),
+                Err(e) => tracing::error!("failed to load snapshot {}: {e}", path.display()),
+            }
+        }
+        Ok(snapshots)
+    }
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    ActionError, ExecutiveAction, ExecutiveActionResponse, ExecutiveActionTask, GameState, Law,
+    Role, Task, TaskAction,
+};
+
+fn assert_rejected(game: &mut GameState, player: &str, action: TaskAction, error: ActionError) {
+    let entries = game.log().entries.len();
+    assert_eq!(game.on_action(player.to_string(), action), Err(error));
+    assert_eq!(
+        game.log().entries.len(),
+        entries,
+        "rejected action was logged"
+    );
+}
+
+#[test]
+fn players_only_act_on_their_turn() {
+    let (mut game, ids) = started_game(5);
+    let (president, Task::ChooseChancellor(options)) = task_holder(&game, &ids).unwrap() else {
+        panic!("no president is choosing a chancellor");
+    };
+    let other = ids.iter().find(|id| **id != president).unwrap();
+    assert_rejected(
+        &mut game,
+        other,
+        TaskAction::ChooseChancellor(options[0].clone()),
+        ActionError::NotYourTurn,
+    );
+}
+
+#[test]
+fn the_dead_do_not_act() {
+    let (mut game, ids) = started_game_with(fascist_config(&[Some(ExecutiveAction::Kill)]), 7);
+    let president = pass_fascist_law(&mut game, &ids);
+    let dead = game
+        .players()
+        .iter()
+        .find(|p| p.role != Role::FashoHitler && p.id != president)
+        .unwrap()
+        .id
+        .clone();
+    game.on_action(
+        president,
+        TaskAction::ExecuteAction(ExecutiveActionResponse::Kill(dead.clone())),
+    )
+    .unwrap();
+
+    let (next, Task::ChooseChancellor(options)) = task_holder(&game, &ids).unwrap() else {
+        panic!("no president is choosing a chancellor");
+    };
+    game.on_action(next, TaskAction::ChooseChancellor(options[0].clone()))
+        .unwrap();
+    assert_eq!(game.tasks(&dead), None);
+    assert_rejected(&mut game, &dead, TaskAction::Vote(true), ActionError::Dead);
+}
+
+#[test]
+fn strangers_do_not_act() {
+    let (mut game, ids) = started_game(5);
+    let (_, Task::ChooseChancellor(options)) = task_holder(&game, &ids).unwrap() else {
+        panic!("no president is choosing a chancellor");
+    };
+    for action in [
+        TaskAction::ChooseChancellor(options[0].clone()),
+        TaskAction::Vote(true),
+        TaskAction::Restart,
+    ] {
+        assert_rejected(&mut game, "stranger", action, ActionError::NotAPlayer);
+    }
+}
+
+#[test]
+fn only_the_president_takes_presidential_actions() {
+    let (mut game, ids) =
+        started_game_with(fascist_config(&[Some(ExecutiveAction::RevealFaction)]), 7);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    let laws = dealt_laws(&game, &president);
+    assert_rejected(
+        &mut game,
+        &chancellor,
+        TaskAction::PickedLaws(laws[..2].to_vec(), laws[2]),
+        ActionError::NotYourTurn,
+    );
+
+    enact(&mut game, &president, &chancellor, Law::Fasho);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::RevealFaction(targets))) =
+        game.tasks(&president)
+    else {
+        panic!("{president} may not investigate");
+    };
+    for id in ids.iter().filter(|id| **id != president) {
+        assert_rejected(
+            &mut game,
+            id,
+            TaskAction::ExecuteAction(ExecutiveActionResponse::RevealFaction(targets[0].clone())),
+            ActionError::NotYourTurn,
+        );
+    }
+}
@@ -0,0 +1,40 @@
+mod common;
+
+use common::*;
+use rand::{rngs::StdRng, SeedableRng};
+use secret_hitler::{bot, Config, GameState, SpectatorMode, StateView, TaskAction};
+
+#[test]
+fn bots_play_complete_games() {
+    for n in 5..=10 {
+        for seed in 0..10 {
+            let mut game = bot_game(n, seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+            bot::run_bots(&mut game, &mut rng);
+
+            let view = game.spectator_view(SpectatorMode::Public);
+            assert!(
+                matches!(view.state, StateView::GameOver { .. }),
+                "{n} bots with seed {seed} got stuck in {:?}",
+                view.state
+            );
+        }
+    }
+}
+
+#[test]
+fn bots_wait_for_humans() {
+    let mut game = GameState::new(Config::default());
+    let human = game.add_new_user(user("human")).unwrap().id;
+    for _ in 0..4 {
+        game.add_bot(None).unwrap();
+    }
+    game.on_action(human.clone(), TaskAction::Start).unwrap();
+
+    bot::run_bots(&mut game, &mut StdRng::seed_from_u64(0));
+    let view = game.view(&human).unwrap();
+    assert!(
+        game.tasks(&human).is_some() || matches!(view.state, StateView::GameOver { .. }),
+        "bots stopped without waiting for the human"
+    );
+}
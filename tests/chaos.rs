@@ -0,0 +1,119 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    Config, Event, ExecutiveAction, GameState, Law, PlayerId, Rules, SpectatorMode, StateView,
+    TaskAction,
+};
+
+#[test]
+fn chaos_enacts_the_top_law_and_resets_the_tracker() {
+    for seed in 0..20 {
+        let (mut game, ids) = started_game_with(
+            Config {
+                seed: Some(seed),
+                ..Config::default()
+            },
+            7,
+        );
+        for _ in 0..3 {
+            fail_election(&mut game, &ids);
+        }
+
+        let board = game.spectator_view(SpectatorMode::Public).board;
+        let Some(Event::Chaos { law }) = board.history.last() else {
+            panic!("chaos was not recorded, history: {:?}", board.history);
+        };
+        let passed = match law {
+            Law::Liberal => board.passed_liberal_laws,
+            Law::Fasho => board.passed_fasho_laws,
+        };
+        assert_eq!(passed, 1);
+        assert_eq!(board.passed_liberal_laws + board.passed_fasho_laws, 1);
+        assert_eq!(board.no_goverment_counter, 0);
+        assert_eq!(board.previous_president, None);
+        assert_eq!(board.previous_chancellor, None);
+    }
+}
+
+#[test]
+fn chaos_grants_no_executive_action() {
+    let rules = fascist_rules(&[Some(ExecutiveAction::Kill); 6]);
+
+    let mut fascist_chaos = 0;
+    for seed in 0..20 {
+        let cfg = Config {
+            seed: Some(seed),
+            rules: rules.clone(),
+            ..Config::default()
+        };
+        let (mut game, ids) = started_game_with(cfg, 5);
+        for _ in 0..3 {
+            fail_election(&mut game, &ids);
+        }
+
+        let view = game.spectator_view(SpectatorMode::Public);
+        if view.board.passed_fasho_laws == 1 {
+            fascist_chaos += 1;
+            assert!(
+                matches!(view.state, StateView::ChooseChancellor { .. }),
+                "chaos granted {:?}",
+                view.state
+            );
+        }
+    }
+    assert!(fascist_chaos > 0);
+}
+
+/// Elects a government that vetoes its laws.
+fn veto(game: &mut GameState, ids: &[PlayerId]) {
+    let (president, chancellor) = elect_government(game, ids);
+    let laws = dealt_laws(game, &president);
+    game.on_action(
+        president.clone(),
+        TaskAction::PickedLaws(laws[..2].to_vec(), laws[2]),
+    )
+    .unwrap();
+    game.on_action(chancellor, TaskAction::Veto(true)).unwrap();
+    game.on_action(president, TaskAction::Veto(true)).unwrap();
+}
+
+fn veto_config(seed: u64) -> Config {
+    Config {
+        seed: Some(seed),
+        rules: Rules {
+            veto_threshold: 0,
+            ..Rules::default()
+        },
+        ..Config::default()
+    }
+}
+
+#[test]
+fn vetoes_advance_the_election_tracker() {
+    let (mut game, ids) = started_game_with(veto_config(0), 7);
+    veto(&mut game, &ids);
+
+    let view = game.spectator_view(SpectatorMode::Public);
+    assert_eq!(view.board.no_goverment_counter, 1);
+    assert_eq!(
+        view.board.passed_liberal_laws + view.board.passed_fasho_laws,
+        0
+    );
+    assert!(matches!(view.state, StateView::ChooseChancellor { .. }));
+}
+
+#[test]
+fn a_veto_can_cause_chaos() {
+    for seed in 0..10 {
+        let (mut game, ids) = started_game_with(veto_config(seed), 7);
+        fail_election(&mut game, &ids);
+        fail_election(&mut game, &ids);
+        veto(&mut game, &ids);
+
+        let board = game.spectator_view(SpectatorMode::Public).board;
+        assert!(matches!(board.history.last(), Some(Event::Chaos { .. })));
+        assert_eq!(board.passed_liberal_laws + board.passed_fasho_laws, 1);
+        assert_eq!(board.no_goverment_counter, 0);
+    }
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    ActionError, ChatChannel, ChatRules, Config, ExecutiveAction, ExecutiveActionResponse,
+    GameState, Law, PlayerId, Role, SpectatorMode, TaskAction, Viewer,
+};
+
+fn texts(game: &GameState, viewer: &Viewer) -> Vec<String> {
+    game.chat_for(viewer, None)
+        .into_iter()
+        .map(|m| m.text)
+        .collect()
+}
+
+fn player(id: &PlayerId) -> Viewer {
+    Viewer::Player(id.clone())
+}
+
+fn with_chat(chat: ChatRules) -> Config {
+    Config {
+        chat,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn the_lobby_chat_is_public() {
+    let mut game = GameState::new(Config::default());
+    let alex = game.add_new_user(user("Alex")).unwrap().id;
+    let sam = game.add_new_user(user("Sam")).unwrap().id;
+
+    game.on_chat(alex.clone(), ChatChannel::All, " hi ".to_string())
+        .unwrap();
+    assert_eq!(texts(&game, &player(&sam)), ["hi"]);
+    assert_eq!(
+        texts(&game, &Viewer::Spectator(SpectatorMode::Public)),
+        ["hi"]
+    );
+
+    assert_eq!(
+        game.on_chat(alex.clone(), ChatChannel::Dead, "boo".to_string()),
+        Err(ActionError::InvalidChannel(ChatChannel::Dead))
+    );
+    assert_eq!(
+        game.on_chat(alex.clone(), ChatChannel::All, " ".to_string()),
+        Err(ActionError::InvalidMessage)
+    );
+    assert_eq!(
+        game.on_chat(alex.clone(), ChatChannel::All, "a".repeat(501)),
+        Err(ActionError::InvalidMessage)
+    );
+
+    game.on_chat(sam, ChatChannel::All, "hey".to_string())
+        .unwrap();
+    let first = game.chat_for(&player(&alex), None)[0].id;
+    assert_eq!(
+        game.chat_for(&player(&alex), Some(first))
+            .into_iter()
+            .map(|m| m.text)
+            .collect::<Vec<_>>(),
+        ["hey"]
+    );
+}
+
+#[test]
+fn the_government_is_muted_during_the_legislative_session() {
+    let (mut game, ids) = started_game(5);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    let other = ids
+        .iter()
+        .find(|id| **id != president && **id != chancellor)
+        .unwrap();
+
+    for id in [&president, &chancellor] {
+        assert_eq!(
+            game.on_chat(id.clone(), ChatChannel::All, "trust me".to_string()),
+            Err(ActionError::Muted)
+        );
+    }
+    game.on_chat(other.clone(), ChatChannel::All, "hurry".to_string())
+        .unwrap();
+
+    enact(&mut game, &president, &chancellor, Law::Liberal);
+    game.on_chat(president, ChatChannel::All, "told you".to_string())
+        .unwrap();
+
+    let (mut game, ids) = started_game_with(
+        with_chat(ChatRules {
+            mute_legislative: false,
+            ..ChatRules::default()
+        }),
+        5,
+    );
+    let (president, _) = elect_government(&mut game, &ids);
+    game.on_chat(president, ChatChannel::All, "trust me".to_string())
+        .unwrap();
+}
+
+#[test]
+fn dead_players_only_talk_to_each_other() {
+    let (mut game, ids) = started_game_with(fascist_config(&[Some(ExecutiveAction::Kill)]), 7);
+    let president = pass_fascist_law(&mut game, &ids);
+    let dead = game
+        .players()
+        .iter()
+        .find(|p| p.role != Role::FashoHitler && p.id != president)
+        .unwrap()
+        .id
+        .clone();
+    game.on_action(
+        president.clone(),
+        TaskAction::ExecuteAction(ExecutiveActionResponse::Kill(dead.clone())),
+    )
+    .unwrap();
+
+    assert_eq!(
+        game.on_chat(dead.clone(), ChatChannel::All, "it was them".to_string()),
+        Err(ActionError::Muted)
+    );
+    game.on_chat(dead.clone(), ChatChannel::Dead, "so lonely".to_string())
+        .unwrap();
+    assert_eq!(
+        game.on_chat(president.clone(), ChatChannel::Dead, "hello?".to_string()),
+        Err(ActionError::InvalidChannel(ChatChannel::Dead))
+    );
+
+    assert_eq!(texts(&game, &player(&dead)), ["so lonely"]);
+    assert!(texts(&game, &player(&president)).is_empty());
+    assert!(texts(&game, &Viewer::Spectator(SpectatorMode::Public)).is_empty());
+}
+
+#[test]
+fn fascists_talk_in_private() {
+    let (mut game, _) = started_game(7);
+    let fasho = game
+        .players()
+        .iter()
+        .find(|p| p.role == Role::Fasho)
+        .unwrap()
+        .id
+        .clone();
+    assert_eq!(
+        game.on_chat(fasho, ChatChannel::Fasho, "psst".to_string()),
+        Err(ActionError::InvalidChannel(ChatChannel::Fasho))
+    );
+
+    let (mut game, _) = started_game_with(
+        with_chat(ChatRules {
+            fasho_channel: true,
+            ..ChatRules::default()
+        }),
+        7,
+    );
+    let of_role = |role| {
+        game.players()
+            .iter()
+            .find(|p| p.role == role)
+            .unwrap()
+            .id
+            .clone()
+    };
+    let (fasho, hitler, liberal) = (
+        of_role(Role::Fasho),
+        of_role(Role::FashoHitler),
+        of_role(Role::Liberal),
+    );
+
+    game.on_chat(fasho.clone(), ChatChannel::Fasho, "psst".to_string())
+        .unwrap();
+    assert_eq!(
+        game.on_chat(liberal.clone(), ChatChannel::Fasho, "psst".to_string()),
+        Err(ActionError::InvalidChannel(ChatChannel::Fasho))
+    );
+    // With seven players, Hitler does not know the other fascists.
+    assert_eq!(
+        game.on_chat(hitler.clone(), ChatChannel::Fasho, "psst".to_string()),
+        Err(ActionError::InvalidChannel(ChatChannel::Fasho))
+    );
+
+    assert_eq!(texts(&game, &player(&fasho)), ["psst"]);
+    assert!(texts(&game, &player(&hitler)).is_empty());
+    assert!(texts(&game, &player(&liberal)).is_empty());
+}
@@ -0,0 +1,143 @@
+#![allow(dead_code)]
+
+use secret_hitler::{
+    Config, ExecutiveAction, GameState, Law, PlayerId, Rules, Task, TaskAction, User,
+};
+
+pub fn user(name: &str) -> User {
+    User {
+        name: name.to_string(),
+        image: String::new(),
+        color: String::new(),
+    }
+}
+
+/// Creates a game with `n` seated players and starts it.
+pub fn started_game(n: usize) -> (GameState, Vec<PlayerId>) {
+    started_game_with(Config::default(), n)
+}
+
+pub fn started_game_with(cfg: Config, n: usize) -> (GameState, Vec<PlayerId>) {
+    let mut game = GameState::new(cfg);
+    let ids = (0..n)
+        .map(|i| game.add_new_user(user(&format!("p{i}"))).unwrap().id)
+        .collect::<Vec<_>>();
+    game.on_action(ids[0].clone(), TaskAction::Start).unwrap();
+    (game, ids)
+}
+
+/// Rules under which every law is fascist, save one, so that every
+/// government unlocks the next slot of `track`.
+pub fn fascist_rules(track: &[Option<ExecutiveAction>]) -> Rules {
+    let mut executive_track = track.to_vec();
+    executive_track.resize(6, None);
+    Rules {
+        liberal_laws: 1,
+        liberal_track: 1,
+        fasho_laws: 16,
+        executive_track: Some(executive_track),
+        ..Rules::default()
+    }
+}
+
+pub fn fascist_config(track: &[Option<ExecutiveAction>]) -> Config {
+    Config {
+        rules: fascist_rules(track),
+        ..Config::default()
+    }
+}
+
+/// Creates a game of `n` bots and starts it.
+pub fn bot_game(n: usize, seed: u64) -> GameState {
+    let mut game = GameState::new(Config {
+        seed: Some(seed),
+        ..Config::default()
+    });
+    let ids = (0..n)
+        .map(|_| game.add_bot(None).unwrap().id)
+        .collect::<Vec<_>>();
+    game.on_action(ids[0].clone(), TaskAction::Start).unwrap();
+    game
+}
+
+/// Returns the first player that currently holds a task.
+pub fn task_holder(game: &GameState, ids: &[PlayerId]) -> Option<(PlayerId, Task)> {
+    ids.iter()
+        .find_map(|id| Some((id.clone(), game.tasks(id)?)))
+}
+
+/// Nominates the first eligible chancellor and lets everyone vote yes.
+/// Returns the elected president and chancellor.
+pub fn elect_government(game: &mut GameState, ids: &[PlayerId]) -> (PlayerId, PlayerId) {
+    let Some((president, Task::ChooseChancellor(options))) = task_holder(game, ids) else {
+        panic!("no president is choosing a chancellor");
+    };
+    let chancellor = options[0].clone();
+    game.on_action(
+        president.clone(),
+        TaskAction::ChooseChancellor(chancellor.clone()),
+    )
+    .unwrap();
+
+    for id in ids {
+        if let Some(Task::Vote(_)) = game.tasks(id) {
+            game.on_action(id.clone(), TaskAction::Vote(true)).unwrap();
+        }
+    }
+    (president, chancellor)
+}
+
+/// The laws a player currently has to choose from.
+pub fn dealt_laws(game: &GameState, id: &PlayerId) -> Vec<Law> {
+    match game.tasks(id) {
+        Some(Task::PickLaws(laws, _)) => laws,
+        task => panic!("{id} is not picking laws, but has {task:?}"),
+    }
+}
+
+pub fn flip(law: Law) -> Law {
+    match law {
+        Law::Liberal => Law::Fasho,
+        Law::Fasho => Law::Liberal,
+    }
+}
+
+/// Passes a law, the president keeping `prefer` laws if possible.
+pub fn enact(game: &mut GameState, president: &PlayerId, chancellor: &PlayerId, prefer: Law) {
+    let mut laws = dealt_laws(game, president);
+    laws.sort_by_key(|law| *law != prefer);
+    game.on_action(
+        president.clone(),
+        TaskAction::PickedLaws(laws[..2].to_vec(), laws[2]),
+    )
+    .unwrap();
+
+    let laws = dealt_laws(game, chancellor);
+    let keep = laws.iter().position(|law| *law == prefer).unwrap_or(0);
+    game.on_action(
+        chancellor.clone(),
+        TaskAction::PickedLaws(vec![laws[keep]], laws[1 - keep]),
+    )
+    .unwrap();
+}
+
+/// Nominates the first eligible chancellor and lets everyone vote no.
+pub fn fail_election(game: &mut GameState, ids: &[PlayerId]) {
+    let Some((president, Task::ChooseChancellor(options))) = task_holder(game, ids) else {
+        panic!("no president is choosing a chancellor");
+    };
+    game.on_action(president, TaskAction::ChooseChancellor(options[0].clone()))
+        .unwrap();
+    for id in ids {
+        if game.tasks(id).is_some() {
+            game.on_action(id.clone(), TaskAction::Vote(false)).unwrap();
+        }
+    }
+}
+
+/// Elects a government that passes a fascist law. Returns its president.
+pub fn pass_fascist_law(game: &mut GameState, ids: &[PlayerId]) -> PlayerId {
+    let (president, chancellor) = elect_government(game, ids);
+    enact(game, &president, &chancellor, Law::Fasho);
+    president
+}
@@ -0,0 +1,101 @@
+mod common;
+
+use common::*;
+use rand::{rngs::StdRng, SeedableRng};
+use secret_hitler::{
+    bot, Event, ExecutiveActionTask, GameState, Law, Rules, SpectatorMode, StateView, Task,
+};
+
+/// Laws that are currently in the hands of the government.
+fn laws_in_hand(game: &GameState) -> usize {
+    let view = game.spectator_view(SpectatorMode::Omniscient);
+    if let StateView::AskVeto { .. } = view.state {
+        return 2;
+    }
+    game.players()
+        .iter()
+        .filter_map(|p| match game.tasks(&p.id) {
+            Some(Task::PickLaws(laws, _)) => Some(laws.len()),
+            _ => None,
+        })
+        .sum()
+}
+
+#[test]
+fn official_deck_has_six_liberal_and_eleven_fascist_laws() {
+    let rules = Rules::default();
+    assert_eq!((rules.liberal_laws, rules.fasho_laws), (6, 11));
+
+    let game = bot_game(5, 0);
+    let board = game.spectator_view(SpectatorMode::Public).board;
+    assert_eq!(board.draw_pile, 17);
+    assert_eq!(board.discard_pile, 0);
+}
+
+#[test]
+fn laws_are_conserved_over_whole_games() {
+    let rules = Rules::default();
+    let total = rules.liberal_laws + rules.fasho_laws;
+
+    for n in 5..=10 {
+        for seed in 0..20 {
+            let mut game = bot_game(n, seed);
+            let mut rng = StdRng::seed_from_u64(seed);
+            let mut peeked: Option<Vec<Law>> = None;
+
+            loop {
+                let actions = bot::bot_actions(&game, &mut rng);
+                if actions.is_empty() {
+                    break;
+                }
+                for (player, action) in actions {
+                    game.on_action(player, action).unwrap();
+
+                    let view = game.spectator_view(SpectatorMode::Omniscient);
+                    let board = &view.board;
+                    // Electing Hitler ends the game with laws still in hand.
+                    if let StateView::GameOver { .. } = view.state {
+                        break;
+                    }
+                    assert_eq!(
+                        board.draw_pile
+                            + board.discard_pile
+                            + board.passed_liberal_laws
+                            + board.passed_fasho_laws
+                            + laws_in_hand(&game),
+                        total,
+                        "{n} players with seed {seed} lost track of a law in {:?}",
+                        view.state
+                    );
+
+                    // The peeked laws are exactly the ones the next president draws.
+                    if let StateView::ExecutiveAction {
+                        action: ExecutiveActionTask::RevealNextCards(cards),
+                        ..
+                    } = &view.state
+                    {
+                        peeked = Some(cards.clone());
+                    }
+                    if let Some(Event::Chaos { .. }) = board.history.last() {
+                        peeked = None;
+                    }
+                    let hand = game.players().iter().find_map(|p| match game.tasks(&p.id) {
+                        Some(Task::PickLaws(laws, _)) if laws.len() == 3 => Some(laws),
+                        _ => None,
+                    });
+                    if let (Some(hand), Some(cards)) = (hand, &peeked) {
+                        assert_eq!(&hand, cards);
+                        peeked = None;
+                    }
+                }
+            }
+            assert!(
+                matches!(
+                    game.spectator_view(SpectatorMode::Public).state,
+                    StateView::GameOver { .. }
+                ),
+                "{n} bots with seed {seed} got stuck"
+            );
+        }
+    }
+}
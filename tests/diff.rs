@@ -0,0 +1,71 @@
+mod common;
+
+use common::*;
+use rand::{rngs::StdRng, SeedableRng};
+use secret_hitler::{apply, bot, diff, GameState, PatchError, PatchOp};
+use serde_json::{json, Value};
+
+#[test]
+fn patches_follow_a_whole_game() {
+    let mut game = bot_game(7, 7);
+    let viewer = game.players()[3].id.clone();
+
+    let mut rng = StdRng::seed_from_u64(7);
+    let view = |game: &GameState| serde_json::to_value(game.view(&viewer)).unwrap();
+    let mut client = view(&game);
+    loop {
+        let actions = bot::bot_actions(&game, &mut rng);
+        if actions.is_empty() {
+            break;
+        }
+        for (player, action) in actions {
+            game.on_action(player, action).unwrap();
+        }
+
+        let current = view(&game);
+        let patch = diff(&client, &current);
+        apply(&mut client, &patch).unwrap();
+        assert_eq!(client, current);
+    }
+}
+
+#[test]
+fn appending_only_sends_the_new_elements() {
+    let from = json!({ "history": [1, 2], "round": 1 });
+    let to = json!({ "history": [1, 2, 3], "round": 1 });
+    assert_eq!(
+        diff(&from, &to),
+        vec![PatchOp::Add {
+            path: "/history/2".to_string(),
+            value: json!(3),
+        }]
+    );
+    assert!(diff(&to, &to).is_empty());
+}
+
+#[test]
+fn keys_are_escaped() {
+    let from = json!({ "a/b": { "~": 1 }, "gone": true });
+    let to = json!({ "a/b": { "~": 2 }, "list": [null] });
+    let patch = diff(&from, &to);
+    assert!(patch.contains(&PatchOp::Replace {
+        path: "/a~1b/~0".to_string(),
+        value: json!(2),
+    }));
+
+    let mut doc = from.clone();
+    apply(&mut doc, &patch).unwrap();
+    assert_eq!(doc, to);
+}
+
+#[test]
+fn patches_for_other_documents_are_rejected() {
+    let patch = vec![PatchOp::Remove {
+        path: "/history/4".to_string(),
+    }];
+    let mut doc: Value = json!({ "history": [] });
+    assert_eq!(
+        apply(&mut doc, &patch),
+        Err(PatchError::NoSuchPath("/history/4".to_string()))
+    );
+}
@@ -0,0 +1,141 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    ActionError, ExecutiveAction, ExecutiveActionResponse, ExecutiveActionTask, GameState,
+    PlayerId, Role, Task, TaskAction,
+};
+
+/// A game of `n` in which every law is fascist, save one, and the first
+/// two fascist laws grant `action`.
+fn game_with_action(action: ExecutiveAction, n: usize) -> (GameState, Vec<PlayerId>) {
+    started_game_with(fascist_config(&[Some(action), Some(action)]), n)
+}
+
+fn execute(
+    game: &mut GameState,
+    president: &PlayerId,
+    response: ExecutiveActionResponse,
+) -> Result<(), ActionError> {
+    game.on_action(president.clone(), TaskAction::ExecuteAction(response))
+}
+
+fn harmless_target(game: &GameState, targets: &[PlayerId]) -> PlayerId {
+    game.players()
+        .iter()
+        .find(|p| p.role != Role::FashoHitler && targets.contains(&p.id))
+        .map(|p| p.id.clone())
+        .unwrap()
+}
+
+#[test]
+fn nobody_kills_themselves_or_the_dead() {
+    let (mut game, ids) = game_with_action(ExecutiveAction::Kill, 7);
+    let president = pass_fascist_law(&mut game, &ids);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::Kill(targets))) = game.tasks(&president)
+    else {
+        panic!("{president} may not kill");
+    };
+    assert!(!targets.contains(&president));
+    assert_eq!(
+        execute(
+            &mut game,
+            &president,
+            ExecutiveActionResponse::Kill(president.clone())
+        ),
+        Err(ActionError::InvalidTarget(president.clone()))
+    );
+
+    let dead = harmless_target(&game, &targets);
+    execute(
+        &mut game,
+        &president,
+        ExecutiveActionResponse::Kill(dead.clone()),
+    )
+    .unwrap();
+
+    let president = pass_fascist_law(&mut game, &ids);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::Kill(targets))) = game.tasks(&president)
+    else {
+        panic!("{president} may not kill");
+    };
+    assert!(!targets.contains(&dead));
+    assert_eq!(
+        execute(
+            &mut game,
+            &president,
+            ExecutiveActionResponse::Kill(dead.clone())
+        ),
+        Err(ActionError::InvalidTarget(dead))
+    );
+}
+
+#[test]
+fn nobody_is_investigated_twice() {
+    let (mut game, ids) = game_with_action(ExecutiveAction::RevealFaction, 7);
+    let president = pass_fascist_law(&mut game, &ids);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::RevealFaction(targets))) =
+        game.tasks(&president)
+    else {
+        panic!("{president} may not investigate");
+    };
+    assert_eq!(
+        execute(
+            &mut game,
+            &president,
+            ExecutiveActionResponse::RevealFaction(president.clone())
+        ),
+        Err(ActionError::InvalidTarget(president.clone()))
+    );
+
+    let investigated = targets[0].clone();
+    execute(
+        &mut game,
+        &president,
+        ExecutiveActionResponse::RevealFaction(investigated.clone()),
+    )
+    .unwrap();
+
+    let president = pass_fascist_law(&mut game, &ids);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::RevealFaction(targets))) =
+        game.tasks(&president)
+    else {
+        panic!("{president} may not investigate");
+    };
+    assert!(!targets.contains(&investigated));
+    assert_eq!(
+        execute(
+            &mut game,
+            &president,
+            ExecutiveActionResponse::RevealFaction(investigated.clone())
+        ),
+        Err(ActionError::InvalidTarget(investigated))
+    );
+}
+
+#[test]
+fn the_president_does_not_appoint_themselves() {
+    let (mut game, ids) = game_with_action(ExecutiveAction::DeterminePresident, 7);
+    let president = pass_fascist_law(&mut game, &ids);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::DeterminePresident(targets))) =
+        game.tasks(&president)
+    else {
+        panic!("{president} may not appoint a president");
+    };
+    assert!(!targets.contains(&president));
+    assert_eq!(targets.len(), 6);
+    assert_eq!(
+        execute(
+            &mut game,
+            &president,
+            ExecutiveActionResponse::DeterminePresident(president.clone())
+        ),
+        Err(ActionError::InvalidTarget(president.clone()))
+    );
+    execute(
+        &mut game,
+        &president,
+        ExecutiveActionResponse::DeterminePresident(targets[0].clone()),
+    )
+    .unwrap();
+}
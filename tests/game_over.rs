@@ -0,0 +1,220 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    Config, ExecutiveAction, ExecutiveActionResponse, ExecutiveActionTask, Faction, GameState, Law,
+    PlayerId, Role, Rules, SpectatorMode, StateView, Task, TaskAction, WinReason,
+};
+
+/// Rules under which a single law decides the game.
+fn one_law_rules(liberal_laws: usize, fasho_laws: usize) -> Config {
+    Config {
+        rules: Rules {
+            liberal_laws,
+            fasho_laws,
+            liberal_track: 1,
+            fasho_track: 1,
+            hitler_threshold: 1,
+            veto_threshold: 1,
+            executive_track: Some(vec![None]),
+            ..Rules::default()
+        },
+        ..Config::default()
+    }
+}
+
+fn result(game: &GameState) -> (Faction, WinReason) {
+    match game.spectator_view(SpectatorMode::Public).state {
+        StateView::GameOver { winner, reason } => (winner, reason),
+        state => panic!("the game is not over, but in {state:?}"),
+    }
+}
+
+fn hitler(game: &GameState) -> PlayerId {
+    game.players()
+        .iter()
+        .find(|p| p.role == Role::FashoHitler)
+        .unwrap()
+        .id
+        .clone()
+}
+
+#[test]
+fn liberals_win_by_policy() {
+    let (mut game, ids) = started_game_with(one_law_rules(16, 1), 5);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    enact(&mut game, &president, &chancellor, Law::Liberal);
+    assert_eq!(result(&game), (Faction::Liberal, WinReason::LiberalLaws));
+}
+
+#[test]
+fn fascists_win_by_policy() {
+    let (mut game, ids) = started_game_with(one_law_rules(1, 16), 5);
+    pass_fascist_law(&mut game, &ids);
+    assert_eq!(result(&game), (Faction::Fasho, WinReason::FashoLaws));
+}
+
+#[test]
+fn electing_hitler_chancellor_wins_for_the_fascists() {
+    let rules = Rules {
+        hitler_threshold: 0,
+        ..Rules::default()
+    };
+    let (mut game, ids) = started_game_with(
+        Config {
+            rules,
+            ..Config::default()
+        },
+        5,
+    );
+    let hitler = hitler(&game);
+    // Hitler cannot nominate himself.
+    while task_holder(&game, &ids).unwrap().0 == hitler {
+        fail_election(&mut game, &ids);
+    }
+
+    let (president, _) = task_holder(&game, &ids).unwrap();
+    game.on_action(president, TaskAction::ChooseChancellor(hitler))
+        .unwrap();
+    for id in &ids {
+        game.on_action(id.clone(), TaskAction::Vote(true)).unwrap();
+    }
+    assert_eq!(result(&game), (Faction::Fasho, WinReason::HitlerElected));
+}
+
+/// A game of seven in which the first president killed Hitler.
+fn hitler_killed() -> (GameState, Vec<PlayerId>) {
+    loop {
+        let (mut game, ids) = started_game_with(fascist_config(&[Some(ExecutiveAction::Kill)]), 7);
+        let president = pass_fascist_law(&mut game, &ids);
+        let hitler = hitler(&game);
+        // Hitler cannot kill himself.
+        if president != hitler {
+            game.on_action(
+                president,
+                TaskAction::ExecuteAction(ExecutiveActionResponse::Kill(hitler)),
+            )
+            .unwrap();
+            return (game, ids);
+        }
+    }
+}
+
+#[test]
+fn killing_hitler_wins_for_the_liberals() {
+    let (game, ids) = hitler_killed();
+    assert_eq!(result(&game), (Faction::Liberal, WinReason::HitlerKilled));
+    assert_eq!(task_holder(&game, &ids), None);
+}
+
+#[test]
+fn the_end_reveals_all_roles() {
+    let (mut game, ids) = started_game_with(one_law_rules(1, 16), 5);
+    let liberal = game
+        .players()
+        .iter()
+        .find(|p| p.role == Role::Liberal)
+        .unwrap()
+        .id
+        .clone();
+    let view = game.view(&liberal).unwrap();
+    assert_eq!(
+        view.board
+            .players
+            .iter()
+            .filter(|p| p.role.is_some())
+            .count(),
+        1
+    );
+
+    pass_fascist_law(&mut game, &ids);
+    let roles = game
+        .players()
+        .iter()
+        .map(|p| Some(p.role))
+        .collect::<Vec<_>>();
+    for view in [
+        game.view(&liberal).unwrap(),
+        game.spectator_view(SpectatorMode::Public),
+    ] {
+        let revealed = view
+            .board
+            .players
+            .iter()
+            .map(|p| p.role)
+            .collect::<Vec<_>>();
+        assert_eq!(revealed, roles);
+    }
+}
+
+#[test]
+fn a_rematch_starts_over() {
+    let (mut game, ids) = hitler_killed();
+    assert!(game.is_over());
+
+    game.on_action(ids[0].clone(), TaskAction::Start).unwrap();
+    assert!(game.is_running());
+    assert!(game.players().iter().all(|p| p.alive));
+    let board = game.spectator_view(SpectatorMode::Public).board;
+    assert_eq!(board.passed_fasho_laws, 0);
+    assert_eq!(board.previous_president, None);
+    assert_eq!(board.previous_chancellor, None);
+    assert!(board.history.is_empty());
+    assert!(board.players.iter().all(|p| p.alive && p.role.is_none()));
+    assert!(matches!(
+        task_holder(&game, &ids),
+        Some((_, Task::ChooseChancellor(_)))
+    ));
+}
+
+#[test]
+fn players_leave_a_finished_game() {
+    let cfg = Config {
+        rules: Rules {
+            liberal_laws: 1,
+            fasho_laws: 16,
+            liberal_track: 1,
+            fasho_track: 2,
+            hitler_threshold: 2,
+            veto_threshold: 2,
+            executive_track: Some(vec![Some(ExecutiveAction::RevealFaction), None]),
+            ..Rules::default()
+        },
+        ..Config::default()
+    };
+    let (mut game, ids) = started_game_with(cfg, 7);
+    let investigator = pass_fascist_law(&mut game, &ids);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::RevealFaction(targets))) =
+        game.tasks(&investigator)
+    else {
+        panic!("{investigator} may not investigate");
+    };
+    let investigated = targets[0].clone();
+    game.on_action(
+        investigator.clone(),
+        TaskAction::ExecuteAction(ExecutiveActionResponse::RevealFaction(investigated.clone())),
+    )
+    .unwrap();
+    let last_president = pass_fascist_law(&mut game, &ids);
+    assert_eq!(result(&game), (Faction::Fasho, WinReason::FashoLaws));
+
+    // The investigated player and the president the rotation points at leave.
+    game.remove_player(&investigated);
+    game.remove_player(&last_president);
+    let remaining = ids
+        .iter()
+        .filter(|id| **id != investigated && **id != last_president)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(game.players().len(), remaining.len());
+    for id in &remaining {
+        game.view(id).unwrap();
+    }
+    game.spectator_view(SpectatorMode::Public);
+
+    let host = game.host().unwrap().id.clone();
+    game.on_action(host, TaskAction::Start).unwrap();
+    assert!(game.is_running());
+    let (president, _) = elect_government(&mut game, &remaining);
+    assert!(remaining.contains(&president));
+}
@@ -0,0 +1,248 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    ActionError, Config, ExecutiveAction, ExecutiveActionResponse, ExecutiveActionTask, GameState,
+    HostAction, LogEntry, Role, SpectatorMode, Task, TaskAction,
+};
+
+fn lobby(n: usize) -> (GameState, Vec<String>) {
+    let mut game = GameState::new(Config::default());
+    let ids = (0..n)
+        .map(|i| game.add_new_user(user(&format!("p{i}"))).unwrap().id)
+        .collect::<Vec<_>>();
+    (game, ids)
+}
+
+#[test]
+fn first_player_hosts() {
+    let (mut game, ids) = lobby(5);
+    assert_eq!(game.host().unwrap().id, ids[0]);
+    assert!(game.view(&ids[1]).unwrap().board.players[0].host);
+
+    assert_eq!(
+        game.on_action(ids[1].clone(), TaskAction::Start),
+        Err(ActionError::NotHost)
+    );
+    assert_eq!(
+        game.on_host_action(ids[1].clone(), HostAction::Kick(ids[2].clone())),
+        Err(ActionError::NotHost)
+    );
+
+    game.remove_player(&ids[0]);
+    assert_eq!(game.host().unwrap().id, ids[1]);
+}
+
+#[test]
+fn humans_host_before_bots() {
+    let mut game = GameState::new(Config::default());
+    let bot = game.add_bot(None).unwrap().id;
+    assert_eq!(game.host().unwrap().id, bot);
+
+    let human = game.add_new_user(user("human")).unwrap().id;
+    assert_eq!(game.host().unwrap().id, human);
+    assert_eq!(
+        game.on_host_action(human, HostAction::TransferHost(bot.clone())),
+        Err(ActionError::InvalidTarget(bot))
+    );
+}
+
+#[test]
+fn host_arranges_the_lobby() {
+    let (mut game, ids) = lobby(6);
+    let host = ids[0].clone();
+
+    game.connect(&ids[5], 1);
+    game.on_host_action(host.clone(), HostAction::Kick(ids[5].clone()))
+        .unwrap();
+    assert_eq!(game.player_count(), 5);
+    assert_eq!(game.session(&ids[5]), None);
+    assert!(!game.is_current_connection(&ids[5], 1));
+
+    game.on_host_action(
+        host.clone(),
+        HostAction::MoveSeat {
+            player: ids[4].clone(),
+            seat: 0,
+        },
+    )
+    .unwrap();
+    let seats = game.players().iter().map(|p| &p.id).collect::<Vec<_>>();
+    assert_eq!(seats, [&ids[4], &ids[0], &ids[1], &ids[2], &ids[3]]);
+    assert_eq!(
+        game.on_host_action(
+            host.clone(),
+            HostAction::MoveSeat {
+                player: ids[4].clone(),
+                seat: 5,
+            },
+        ),
+        Err(ActionError::InvalidSeat(5))
+    );
+
+    game.on_host_action(host.clone(), HostAction::TransferHost(ids[1].clone()))
+        .unwrap();
+    assert_eq!(game.host().unwrap().id, ids[1]);
+    assert_eq!(
+        game.on_host_action(host, HostAction::Abort),
+        Err(ActionError::NotHost)
+    );
+}
+
+#[test]
+fn lobby_is_fixed_while_running() {
+    let (mut game, ids) = started_game(5);
+    assert_eq!(
+        game.on_host_action(ids[0].clone(), HostAction::Kick(ids[1].clone())),
+        Err(ActionError::WrongPhase)
+    );
+    assert_eq!(
+        game.on_host_action(ids[0].clone(), HostAction::EditConfig(Box::default())),
+        Err(ActionError::WrongPhase)
+    );
+}
+
+#[test]
+fn pause_freezes_the_game() {
+    let (mut game, ids) = started_game_with(
+        Config {
+            timeouts: secret_hitler::Timeouts {
+                choose_chancellor: Some(60),
+                ..Default::default()
+            },
+            ..Config::default()
+        },
+        5,
+    );
+    let (president, task) = task_holder(&game, &ids).unwrap();
+
+    game.on_host_action(ids[0].clone(), HostAction::Pause)
+        .unwrap();
+    assert!(game.view(&ids[0]).unwrap().board.paused);
+    assert_eq!(game.deadline(), None);
+    assert_eq!(task_holder(&game, &ids), None);
+    assert_eq!(
+        game.on_action(
+            president.clone(),
+            TaskAction::ChooseChancellor(ids[0].clone())
+        ),
+        Err(ActionError::Paused)
+    );
+
+    game.on_host_action(ids[0].clone(), HostAction::Resume)
+        .unwrap();
+    assert!(game.deadline().is_some());
+    assert_eq!(task_holder(&game, &ids), Some((president, task)));
+}
+
+#[test]
+fn abort_returns_to_the_lobby() {
+    let (mut game, ids) = started_game_with(fascist_config(&[Some(ExecutiveAction::Kill)]), 5);
+    let president = pass_fascist_law(&mut game, &ids);
+    let Some(Task::ExecutiveAction(ExecutiveActionTask::Kill(targets))) = game.tasks(&president)
+    else {
+        panic!("the president may not kill");
+    };
+    // Killing Hitler would end the game.
+    let target = targets
+        .into_iter()
+        .find(|id| {
+            game.players()
+                .iter()
+                .any(|p| &p.id == id && p.role != Role::FashoHitler)
+        })
+        .unwrap();
+    game.on_action(
+        president.clone(),
+        TaskAction::ExecuteAction(ExecutiveActionResponse::Kill(target)),
+    )
+    .unwrap();
+
+    game.on_host_action(ids[0].clone(), HostAction::Abort)
+        .unwrap();
+    assert!(!game.is_running());
+    assert_eq!(task_holder(&game, &ids), None);
+    assert!(game
+        .players()
+        .iter()
+        .all(|p| p.alive && p.role == Role::Liberal));
+    let board = game.spectator_view(SpectatorMode::Public).board;
+    assert_eq!(board.passed_fasho_laws, 0);
+    assert_eq!(board.previous_president, None);
+    assert_eq!(board.previous_chancellor, None);
+    assert!(board.history.is_empty());
+
+    game.on_action(ids[0].clone(), TaskAction::Start).unwrap();
+    assert!(game.is_running());
+}
+
+#[test]
+fn password_holder_claims_host() {
+    let mut game = GameState::new(Config {
+        host_password: Some("hunter2".to_string()),
+        ..Config::default()
+    });
+    let first = game.add_new_user(user("first")).unwrap().id;
+    let admin = game.add_new_user(user("admin")).unwrap().id;
+    assert_eq!(game.host().unwrap().id, first);
+
+    assert_eq!(
+        game.claim_host(&admin, "wrong"),
+        Err(ActionError::WrongPassword)
+    );
+    game.claim_host(&admin, "hunter2").unwrap();
+    assert_eq!(game.host().unwrap().id, admin);
+}
+
+#[test]
+fn requests_authenticate_as_host() {
+    let mut game = GameState::new(Config {
+        host_password: Some("hunter2".to_string()),
+        ..Config::default()
+    });
+    let host = game.add_new_user(user("host")).unwrap();
+    let guest = game.add_new_user(user("guest")).unwrap();
+
+    game.authorize_host_credential("hunter2").unwrap();
+    game.authorize_host_credential(&host.access_key).unwrap();
+    assert_eq!(
+        game.authorize_host_credential(&guest.access_key),
+        Err(ActionError::NotHost)
+    );
+    for wrong in ["", "hunter3"] {
+        assert_eq!(
+            game.authorize_host_credential(wrong),
+            Err(ActionError::WrongPassword)
+        );
+    }
+}
+
+#[test]
+fn config_edits_replay_and_stay_secret() {
+    let (mut game, ids) = lobby(5);
+    game.on_host_action(
+        ids[0].clone(),
+        HostAction::EditConfig(Box::new(Config {
+            no_votes: true,
+            host_password: Some("hunter2".to_string()),
+            ..Config::default()
+        })),
+    )
+    .unwrap();
+    assert!(game.config().no_votes);
+    assert_eq!(game.config().seed, game.log().config.as_ref().unwrap().seed);
+    game.on_action(ids[0].clone(), TaskAction::Start).unwrap();
+
+    let replayed = GameState::replay(Config::default(), game.log().clone()).unwrap();
+    assert_eq!(replayed.config(), game.config());
+    assert_eq!(replayed.view(&ids[1]), game.view(&ids[1]));
+
+    let redacted = game.log().redacted(false);
+    assert!(redacted.entries.iter().all(|entry| match entry {
+        LogEntry::Host {
+            action: HostAction::EditConfig(cfg),
+            ..
+        } => cfg.host_password.is_none(),
+        _ => true,
+    }));
+}
@@ -0,0 +1,112 @@
+mod common;
+
+use common::*;
+use secret_hitler::{ActionError, GameState, Law, PlayerId, Task, TaskAction};
+
+fn president_picking() -> (GameState, PlayerId, PlayerId, Vec<Law>) {
+    let (mut game, ids) = started_game(5);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    let laws = dealt_laws(&game, &president);
+    assert_eq!(laws.len(), 3);
+    (game, president, chancellor, laws)
+}
+
+fn chancellor_picking() -> (GameState, PlayerId, Vec<Law>) {
+    let (mut game, president, chancellor, laws) = president_picking();
+    game.on_action(
+        president,
+        TaskAction::PickedLaws(laws[..2].to_vec(), laws[2]),
+    )
+    .unwrap();
+    let laws = dealt_laws(&game, &chancellor);
+    assert_eq!(laws.len(), 2);
+    (game, chancellor, laws)
+}
+
+fn assert_rejected(game: &mut GameState, player: &PlayerId, keep: Vec<Law>, discard: Law) {
+    let before = game.tasks(player);
+    assert_eq!(
+        game.on_action(player.clone(), TaskAction::PickedLaws(keep, discard)),
+        Err(ActionError::BadLawSelection)
+    );
+    assert_eq!(
+        game.tasks(player),
+        before,
+        "rejected selection changed the game"
+    );
+}
+
+#[test]
+fn president_honest_selection_is_accepted() {
+    let (mut game, president, chancellor, laws) = president_picking();
+    game.on_action(
+        president,
+        TaskAction::PickedLaws(vec![laws[2], laws[0]], laws[1]),
+    )
+    .unwrap();
+
+    let mut expected = vec![laws[2], laws[0]];
+    let mut dealt = dealt_laws(&game, &chancellor);
+    expected.sort_by_key(|l| *l as u8);
+    dealt.sort_by_key(|l| *l as u8);
+    assert_eq!(dealt, expected);
+}
+
+#[test]
+fn president_cannot_conjure_a_kept_law() {
+    let (mut game, president, _, laws) = president_picking();
+    assert_rejected(&mut game, &president, vec![flip(laws[0]), laws[1]], laws[2]);
+}
+
+#[test]
+fn president_cannot_conjure_the_discarded_law() {
+    let (mut game, president, _, laws) = president_picking();
+    assert_rejected(&mut game, &president, vec![laws[0], laws[1]], flip(laws[2]));
+}
+
+#[test]
+fn president_must_pass_exactly_two_laws() {
+    let (mut game, president, _, laws) = president_picking();
+    assert_rejected(&mut game, &president, vec![laws[0]], laws[2]);
+    assert_rejected(&mut game, &president, laws.clone(), laws[2]);
+    assert_rejected(&mut game, &president, vec![], laws[2]);
+}
+
+#[test]
+fn president_cannot_duplicate_a_law() {
+    // Duplicating is only detectable if the deal contains both kinds of laws.
+    let (mut game, president, _, laws) = std::iter::repeat_with(president_picking)
+        .find(|(.., laws)| laws.contains(&Law::Liberal) && laws.contains(&Law::Fasho))
+        .unwrap();
+    let rare = if laws.iter().filter(|l| **l == Law::Liberal).count() == 1 {
+        Law::Liberal
+    } else {
+        Law::Fasho
+    };
+    assert_rejected(&mut game, &president, vec![rare, rare], rare);
+}
+
+#[test]
+fn chancellor_honest_selection_is_accepted() {
+    let (mut game, chancellor, laws) = chancellor_picking();
+    game.on_action(
+        chancellor.clone(),
+        TaskAction::PickedLaws(vec![laws[1]], laws[0]),
+    )
+    .unwrap();
+    assert!(!matches!(game.tasks(&chancellor), Some(Task::PickLaws(..))));
+}
+
+#[test]
+fn chancellor_cannot_conjure_a_law() {
+    let (mut game, chancellor, laws) = chancellor_picking();
+    assert_rejected(&mut game, &chancellor, vec![flip(laws[0])], laws[1]);
+    assert_rejected(&mut game, &chancellor, vec![laws[0]], flip(laws[1]));
+}
+
+#[test]
+fn chancellor_must_enact_exactly_one_law() {
+    let (mut game, chancellor, laws) = chancellor_picking();
+    assert_rejected(&mut game, &chancellor, laws.clone(), laws[0]);
+    assert_rejected(&mut game, &chancellor, vec![], laws[0]);
+}
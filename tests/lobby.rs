@@ -0,0 +1,99 @@
+mod common;
+
+use common::*;
+use secret_hitler::{Config, Lobby, LobbyError};
+
+fn with_id(id: &str) -> Config {
+    Config {
+        id: id.to_string(),
+        ..Config::default()
+    }
+}
+
+#[tokio::test]
+async fn ids_must_be_plain_names() {
+    let lobby = Lobby::new();
+    for id in [
+        "../escape",
+        "/etc/passwd",
+        "a b",
+        "dir/game",
+        "ü",
+        &"x".repeat(65),
+    ] {
+        assert_eq!(
+            lobby.create(with_id(id)).await,
+            Err(LobbyError::InvalidId(id.to_string()))
+        );
+    }
+    assert!(lobby.list().await.is_empty());
+
+    for id in ["Game_1", "my-game", &"x".repeat(64)] {
+        assert_eq!(lobby.create(with_id(id)).await.unwrap().id, id);
+    }
+}
+
+#[tokio::test]
+async fn games_are_created_listed_and_closed() {
+    let lobby = Lobby::new();
+    let named = lobby.create(with_id("table")).await.unwrap();
+    let random = lobby.create(Config::default()).await.unwrap();
+    assert_eq!(named.id, "table");
+    assert_eq!(random.id.len(), 6);
+    assert!(!named.running);
+
+    let mut expected = vec![named.clone(), random.clone()];
+    expected.sort_by(|a, b| a.id.cmp(&b.id));
+    assert_eq!(lobby.list().await, expected);
+
+    assert_eq!(
+        lobby.create(with_id("table")).await,
+        Err(LobbyError::GameExists("table".to_string()))
+    );
+
+    lobby.close("table").await.unwrap();
+    assert_eq!(lobby.list().await, [random]);
+    assert_eq!(
+        lobby.get("table").await.err(),
+        Some(LobbyError::NoSuchGame("table".to_string()))
+    );
+    assert_eq!(
+        lobby.close("table").await,
+        Err(LobbyError::NoSuchGame("table".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn listed_games_show_their_players() {
+    let lobby = Lobby::new();
+    lobby.create(with_id("table")).await.unwrap();
+    let game = lobby.get("table").await.unwrap();
+    for i in 0..5 {
+        game.write()
+            .await
+            .add_new_user(user(&format!("p{i}")))
+            .unwrap();
+    }
+
+    let info = &lobby.list().await[0];
+    assert_eq!(info.players, 5);
+    assert!(!info.running);
+}
+
+#[test]
+fn configs_are_read_strictly() {
+    assert_eq!(Config::from_body(b"").unwrap(), Config::default());
+    assert_eq!(Config::from_body(b"{}").unwrap(), Config::default());
+    assert_eq!(
+        Config::from_body(br#"{"id": "table"}"#).unwrap(),
+        with_id("table")
+    );
+    for body in [
+        &b"{"[..],
+        b"config",
+        br#"{"id": 5}"#,
+        br#"{"rules": {"fasho_track": "six"}}"#,
+    ] {
+        assert!(Config::from_body(body).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::*;
+use secret_hitler::{ActionError, Config, GameState, User};
+
+#[test]
+fn ids_are_independent_of_names() {
+    let mut game = GameState::new(Config::default());
+    let alex = game.add_new_user(user("Alex")).unwrap();
+    assert_ne!(alex.id, "Alex");
+
+    assert_eq!(
+        game.add_new_user(user("alex ")),
+        Err(ActionError::NameTaken("alex".to_string()))
+    );
+    assert_eq!(
+        game.add_new_user(user("   ")),
+        Err(ActionError::InvalidName)
+    );
+    assert_eq!(
+        game.join_existing_user("guess".to_string()),
+        Err(ActionError::UnknownAccessKey)
+    );
+    assert!(game.add_bot(Some("ALEX".to_string())).is_err());
+
+    let sam = game.add_new_user(user(" Sam ")).unwrap();
+    assert_eq!(sam.user.name, "Sam");
+    assert_ne!(sam.id, alex.id);
+}
+
+#[test]
+fn players_edit_their_profile_in_the_lobby() {
+    let mut game = GameState::new(Config::default());
+    let alex = game.add_new_user(user("Alex")).unwrap();
+    game.add_new_user(user("Sam")).unwrap();
+
+    let profile = User {
+        name: "Robin".to_string(),
+        image: "p3.png".to_string(),
+        color: "teal".to_string(),
+    };
+    game.edit_user(alex.id.clone(), profile.clone()).unwrap();
+    assert_eq!(game.players()[0].id, alex.id);
+    assert_eq!(game.players()[0].user, profile);
+
+    // Keeping one's own name is fine, taking another's is not.
+    assert_eq!(game.edit_user(alex.id.clone(), user("robin")), Ok(()));
+    assert_eq!(
+        game.edit_user(alex.id.clone(), user("sam")),
+        Err(ActionError::NameTaken("sam".to_string()))
+    );
+    assert_eq!(
+        game.edit_user(alex.id.clone(), user(&"x".repeat(25))),
+        Err(ActionError::InvalidName)
+    );
+
+    let replayed = GameState::replay(game.config().clone(), game.log().clone()).unwrap();
+    assert_eq!(replayed.players(), game.players());
+}
+
+#[test]
+fn profiles_are_frozen_once_the_game_starts() {
+    let (mut game, ids) = started_game(5);
+    assert_eq!(
+        game.edit_user(ids[1].clone(), user("Robin")),
+        Err(ActionError::WrongPhase)
+    );
+}
+
+#[test]
+fn nobody_joins_a_running_game() {
+    let (mut game, ids) = started_game(5);
+    assert_eq!(
+        game.add_new_user(user("Late")),
+        Err(ActionError::WrongPhase)
+    );
+    assert_eq!(game.players().len(), 5);
+
+    // The vote still only waits for the seated players.
+    let (president, _) = elect_government(&mut game, &ids);
+    assert_eq!(dealt_laws(&game, &president).len(), 3);
+}
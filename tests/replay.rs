@@ -0,0 +1,65 @@
+mod common;
+
+use common::*;
+use secret_hitler::{Config, GameState, Replay, TaskAction};
+
+#[test]
+fn replay_reproduces_the_game() {
+    let (mut game, ids) = started_game(7);
+    let (president, _) = elect_government(&mut game, &ids);
+    let laws = dealt_laws(&game, &president);
+    game.on_action(
+        president,
+        TaskAction::PickedLaws(laws[1..].to_vec(), laws[0]),
+    )
+    .unwrap();
+
+    let replayed = GameState::replay(Config::default(), game.log().clone()).unwrap();
+    assert_eq!(replayed.snapshot(), game.snapshot());
+}
+
+#[test]
+fn replay_can_be_stepped() {
+    let (mut game, ids) = started_game(5);
+    elect_government(&mut game, &ids);
+
+    let mut replay = Replay::new(Config::default(), game.log().clone());
+    let mut steps = 0;
+    while let Some(entry) = replay.step() {
+        entry.unwrap();
+        steps += 1;
+        assert_eq!(replay.position(), steps);
+    }
+    assert_eq!(steps, game.log().entries.len());
+    assert_eq!(replay.game().snapshot(), game.snapshot());
+}
+
+#[test]
+fn rejected_actions_are_not_logged() {
+    let (mut game, ids) = started_game(5);
+    let entries = game.log().entries.len();
+    assert!(game
+        .on_action(ids[0].clone(), TaskAction::Vote(true))
+        .is_err());
+    assert_eq!(game.log().entries.len(), entries);
+}
+
+#[test]
+fn restoring_keeps_the_whole_log() {
+    let mut game = GameState::new(Config {
+        host_password: Some("hunter2".to_string()),
+        ..Config::default()
+    });
+    let ids = (0..5)
+        .map(|i| game.add_new_user(user(&format!("p{i}"))).unwrap())
+        .collect::<Vec<_>>();
+    game.claim_host(&ids[1].id, "hunter2").unwrap();
+    game.join_existing_user(ids[2].access_key.clone()).unwrap();
+    game.on_action(ids[1].id.clone(), TaskAction::Start)
+        .unwrap();
+
+    let restored = GameState::restore(game.snapshot());
+    let restored = GameState::restore(restored.snapshot());
+    assert_eq!(restored.log(), game.log());
+    assert_eq!(restored.host().unwrap().id, ids[1].id);
+}
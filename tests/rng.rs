@@ -0,0 +1,116 @@
+mod common;
+
+use common::*;
+use rand::{rngs::StdRng, SeedableRng};
+use secret_hitler::{bot, Config, GameState, PlayerId, Role, TaskAction};
+use serde_json::Value;
+
+fn seeded(seed: u64) -> Config {
+    Config {
+        seed: Some(seed),
+        ..Config::default()
+    }
+}
+
+/// The views of all players, with player ids replaced by seats, since ids
+/// differ between games.
+fn seat_views(game: &GameState, ids: &[PlayerId]) -> Vec<Value> {
+    ids.iter()
+        .map(|id| {
+            let mut view = serde_json::to_string(&game.view(id)).unwrap();
+            for (seat, id) in ids.iter().enumerate() {
+                view = view.replace(id, &format!("seat {seat}"));
+            }
+            // Reparse, so that maps compare regardless of their order.
+            serde_json::from_str(&view).unwrap()
+        })
+        .collect()
+}
+
+fn views(cfg: Config) -> Vec<Value> {
+    let (mut game, ids) = started_game_with(cfg, 8);
+    let (president, _) = elect_government(&mut game, &ids);
+    let laws = dealt_laws(&game, &president);
+    game.on_action(
+        president,
+        TaskAction::PickedLaws(laws[..2].to_vec(), laws[2]),
+    )
+    .unwrap();
+    seat_views(&game, &ids)
+}
+
+#[test]
+fn same_seed_deals_the_same_game() {
+    for seed in 0..20 {
+        assert_eq!(views(seeded(seed)), views(seeded(seed)));
+    }
+}
+
+#[test]
+fn different_seeds_deal_different_games() {
+    let distinct = (0..20).map(|seed| views(seeded(seed))).collect::<Vec<_>>();
+    assert!(distinct.windows(2).any(|w| w[0] != w[1]));
+}
+
+#[test]
+fn unseeded_games_record_their_seed() {
+    let (game, ids) = started_game(5);
+    let (replayed, replayed_ids) = started_game_with(seeded(game.log().seed.unwrap()), 5);
+    assert_eq!(
+        seat_views(&game, &ids),
+        seat_views(&replayed, &replayed_ids)
+    );
+}
+
+fn roles(game: &GameState, ids: &[PlayerId]) -> Vec<Role> {
+    ids.iter()
+        .map(|id| game.players().iter().find(|p| &p.id == id).unwrap().role)
+        .collect()
+}
+
+/// The role of every player and the first president.
+fn deal(game: &GameState, ids: &[PlayerId]) -> (Vec<Role>, PlayerId) {
+    (roles(game, ids), task_holder(game, ids).unwrap().0)
+}
+
+#[test]
+fn lobby_logs_do_not_predict_the_deal() {
+    let mut game = GameState::new(Config::default());
+    let ids = (0..10)
+        .map(|i| game.add_new_user(user(&format!("p{i}"))).unwrap().id)
+        .collect::<Vec<_>>();
+    let log = game.log().redacted(false);
+    assert_eq!(log.seed, None);
+
+    let mut replayed = GameState::replay(Config::default(), log).unwrap();
+    game.on_action(ids[0].clone(), TaskAction::Start).unwrap();
+    replayed
+        .on_action(ids[0].clone(), TaskAction::Start)
+        .unwrap();
+    assert_ne!(deal(&game, &ids), deal(&replayed, &ids));
+}
+
+#[test]
+fn finished_rounds_do_not_predict_the_rematch() {
+    let mut game = bot_game(10, 0);
+    let ids = game
+        .players()
+        .iter()
+        .map(|p| p.id.clone())
+        .collect::<Vec<_>>();
+    bot::run_bots(&mut game, &mut StdRng::seed_from_u64(0));
+    assert!(game.is_over());
+
+    // The seed of the finished round replays it, but not the next one.
+    let log = game.log().redacted(true);
+    assert_eq!(log.seed, None);
+    let mut replayed = GameState::replay(Config::default(), log).unwrap();
+    assert!(replayed.is_over());
+    assert_eq!(roles(&replayed, &ids), roles(&game, &ids));
+
+    game.on_action(ids[0].clone(), TaskAction::Start).unwrap();
+    replayed
+        .on_action(ids[0].clone(), TaskAction::Start)
+        .unwrap();
+    assert_ne!(deal(&game, &ids), deal(&replayed, &ids));
+}
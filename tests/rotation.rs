@@ -0,0 +1,124 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    Config, ExecutiveAction, ExecutiveActionResponse, GameState, PlayerId, Role, SpectatorMode,
+    Task, TaskAction,
+};
+
+/// A game of seven whose deck is nearly all fascist laws, so that every
+/// government unlocks the next slot of `track`.
+fn game_with_track(track: &[Option<ExecutiveAction>], seed: u64) -> (GameState, Vec<PlayerId>) {
+    started_game_with(
+        Config {
+            seed: Some(seed),
+            ..fascist_config(track)
+        },
+        7,
+    )
+}
+
+fn president(game: &GameState, ids: &[PlayerId]) -> PlayerId {
+    match task_holder(game, ids) {
+        Some((id, Task::ChooseChancellor(_))) => id,
+        task => panic!("no president is choosing a chancellor, but {task:?}"),
+    }
+}
+
+fn seat_after(game: &GameState, id: &PlayerId, offset: usize) -> PlayerId {
+    let players = game.players();
+    let idx = players.iter().position(|p| &p.id == id).unwrap();
+    players[(idx + offset) % players.len()].id.clone()
+}
+
+fn call_special_election(game: &mut GameState, caller: &PlayerId, target: &PlayerId) {
+    game.on_action(
+        caller.clone(),
+        TaskAction::ExecuteAction(ExecutiveActionResponse::DeterminePresident(target.clone())),
+    )
+    .unwrap();
+    let ids = game
+        .players()
+        .iter()
+        .map(|p| p.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(&president(game, &ids), target);
+}
+
+#[test]
+fn special_election_of_the_next_player_grants_two_terms() {
+    let (mut game, ids) = game_with_track(&[Some(ExecutiveAction::DeterminePresident)], 0);
+    let caller = pass_fascist_law(&mut game, &ids);
+    let target = seat_after(&game, &caller, 1);
+    call_special_election(&mut game, &caller, &target);
+
+    fail_election(&mut game, &ids);
+    assert_eq!(president(&game, &ids), target);
+}
+
+#[test]
+fn presidency_returns_to_the_left_of_the_caller() {
+    let (mut game, ids) = game_with_track(&[Some(ExecutiveAction::DeterminePresident)], 0);
+    let caller = pass_fascist_law(&mut game, &ids);
+    let target = seat_after(&game, &caller, 3);
+    call_special_election(&mut game, &caller, &target);
+
+    let next = seat_after(&game, &caller, 1);
+    let board = game.spectator_view(SpectatorMode::Public).board;
+    assert_eq!(board.next_president_by_rules, Some(next.clone()));
+
+    fail_election(&mut game, &ids);
+    assert_eq!(president(&game, &ids), next);
+    let board = game.spectator_view(SpectatorMode::Public).board;
+    assert_eq!(board.next_president_by_rules, None);
+}
+
+#[test]
+fn rotation_skips_players_killed_during_a_special_election() {
+    let track = [
+        Some(ExecutiveAction::DeterminePresident),
+        Some(ExecutiveAction::Kill),
+    ];
+    let mut checked = 0;
+    for seed in 0..10 {
+        let (mut game, ids) = game_with_track(&track, seed);
+        let caller = pass_fascist_law(&mut game, &ids);
+        let target = seat_after(&game, &caller, 3);
+        call_special_election(&mut game, &caller, &target);
+
+        let victim = seat_after(&game, &caller, 1);
+        let is_hitler = game
+            .players()
+            .iter()
+            .any(|p| p.id == victim && p.role == Role::FashoHitler);
+        if is_hitler {
+            continue;
+        }
+
+        assert_eq!(pass_fascist_law(&mut game, &ids), target);
+        game.on_action(
+            target,
+            TaskAction::ExecuteAction(ExecutiveActionResponse::Kill(victim)),
+        )
+        .unwrap();
+        assert_eq!(president(&game, &ids), seat_after(&game, &caller, 2));
+        checked += 1;
+    }
+    assert!(checked > 0);
+}
+
+#[test]
+fn chained_special_elections_return_to_the_first_caller() {
+    let track = [Some(ExecutiveAction::DeterminePresident); 2];
+    let (mut game, ids) = game_with_track(&track, 0);
+    let caller = pass_fascist_law(&mut game, &ids);
+    let first = seat_after(&game, &caller, 2);
+    call_special_election(&mut game, &caller, &first);
+
+    assert_eq!(pass_fascist_law(&mut game, &ids), first);
+    let second = seat_after(&game, &caller, 4);
+    call_special_election(&mut game, &first, &second);
+
+    fail_election(&mut game, &ids);
+    assert_eq!(president(&game, &ids), seat_after(&game, &caller, 1));
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    Config, ExecutiveAction, Rules, RulesError, SpectatorMode, StateView, Task, TaskAction,
+    WinReason,
+};
+
+fn with_rules(rules: Rules) -> Config {
+    Config {
+        rules,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn unplayable_rules_are_rejected() {
+    let cases = [
+        (
+            Rules {
+                liberal_laws: 4,
+                ..Rules::default()
+            },
+            RulesError::DeckTooSmall,
+        ),
+        (
+            Rules {
+                veto_threshold: 7,
+                ..Rules::default()
+            },
+            RulesError::VetoThreshold(7),
+        ),
+        (
+            Rules {
+                executive_track: Some(vec![None; 5]),
+                ..Rules::default()
+            },
+            RulesError::ExecutiveTrackLength {
+                expected: 6,
+                actual: 5,
+            },
+        ),
+        (
+            Rules {
+                election_tracker_limit: 0,
+                ..Rules::default()
+            },
+            RulesError::ElectionTrackerLimit,
+        ),
+    ];
+    for (rules, error) in cases {
+        assert_eq!(rules.validate(), Err(error));
+    }
+    assert_eq!(Rules::default().validate(), Ok(()));
+}
+
+#[test]
+fn rules_are_visible_to_clients() {
+    let track = vec![Some(ExecutiveAction::Kill), None, None];
+    let rules = Rules {
+        fasho_track: 3,
+        hitler_threshold: 2,
+        veto_threshold: 2,
+        executive_track: Some(track.clone()),
+        ..Rules::default()
+    };
+    let (game, _) = started_game_with(with_rules(rules.clone()), 5);
+
+    let view = game.spectator_view(SpectatorMode::Public);
+    assert_eq!(view.rules, rules);
+    assert_eq!(view.board.executive_actions, track);
+}
+
+#[test]
+fn election_tracker_limit_is_configurable() {
+    let rules = Rules {
+        election_tracker_limit: 4,
+        ..Rules::default()
+    };
+    let (mut game, ids) = started_game_with(with_rules(rules), 5);
+
+    for _ in 0..3 {
+        let Some((president, Task::ChooseChancellor(options))) = task_holder(&game, &ids) else {
+            panic!("no president is choosing a chancellor");
+        };
+        game.on_action(president, TaskAction::ChooseChancellor(options[0].clone()))
+            .unwrap();
+        for id in &ids {
+            game.on_action(id.clone(), TaskAction::Vote(false)).unwrap();
+        }
+    }
+
+    let board = game.spectator_view(SpectatorMode::Public).board;
+    assert_eq!(board.passed_liberal_laws + board.passed_fasho_laws, 0);
+    assert_eq!(board.no_goverment_counter, 3);
+}
+
+#[test]
+fn win_thresholds_are_configurable() {
+    let rules = Rules {
+        liberal_track: 1,
+        fasho_track: 1,
+        hitler_threshold: 1,
+        veto_threshold: 1,
+        executive_track: Some(vec![None]),
+        ..Rules::default()
+    };
+    let (mut game, ids) = started_game_with(with_rules(rules), 5);
+
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    let laws = dealt_laws(&game, &president);
+    game.on_action(
+        president,
+        TaskAction::PickedLaws(laws[..2].to_vec(), laws[2]),
+    )
+    .unwrap();
+    let laws = dealt_laws(&game, &chancellor);
+    game.on_action(chancellor, TaskAction::PickedLaws(vec![laws[0]], laws[1]))
+        .unwrap();
+
+    let StateView::GameOver { reason, .. } = game.spectator_view(SpectatorMode::Public).state
+    else {
+        panic!("a single law did not end the game");
+    };
+    assert!(matches!(
+        reason,
+        WinReason::LiberalLaws | WinReason::FashoLaws
+    ));
+}
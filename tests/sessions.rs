@@ -0,0 +1,138 @@
+mod common;
+
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+use common::*;
+use futures_util::{SinkExt, StreamExt};
+use secret_hitler::{Config, GameState, Lobby, SpectatorMode};
+use serde_json::{json, Value};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+
+fn access_key(game: &GameState, index: usize) -> String {
+    game.players()[index].access_key.clone()
+}
+
+#[test]
+fn a_new_connection_takes_over_the_old_one() {
+    let mut game = GameState::new(Config::default());
+    let player = game.add_new_user(user("p0")).unwrap();
+    assert_eq!(game.connect(&player.id, 1), None);
+
+    let rejoined = game.join_existing_user(player.access_key.clone()).unwrap();
+    assert_eq!(rejoined.id, player.id);
+    assert_eq!(game.connect(&player.id, 2), Some(1));
+    assert!(!game.is_current_connection(&player.id, 1));
+
+    // The stale tab closing does not affect the new one.
+    game.disconnect(&player.id, 1);
+    assert_eq!(game.players().len(), 1);
+    assert!(game.players()[0].connected);
+    assert!(game.is_current_connection(&player.id, 2));
+
+    game.disconnect(&player.id, 2);
+    assert!(!game.players()[0].connected);
+}
+
+#[test]
+fn lobby_seats_survive_a_dropped_connection() {
+    let mut game = GameState::new(Config::default());
+    let player = game.add_new_user(user("p0")).unwrap();
+    game.connect(&player.id, 1);
+    game.disconnect(&player.id, 1);
+    assert_eq!(game.players().len(), 1);
+    assert!(!game.players()[0].connected);
+
+    game.join_existing_user(player.access_key.clone()).unwrap();
+    game.connect(&player.id, 2);
+    assert!(game.players()[0].connected);
+
+    // Only leaving frees the seat.
+    game.remove_player(&player.id);
+    assert!(game.players().is_empty());
+    assert_eq!(game.session(&player.id), None);
+}
+
+#[test]
+fn players_reconnect_any_number_of_times() {
+    let (mut game, ids) = started_game(5);
+    game.connect(&ids[2], 1);
+
+    for connection in 2..5 {
+        game.disconnect(&ids[2], connection - 1);
+        assert!(!game.players()[2].connected);
+
+        game.join_existing_user(access_key(&game, 2)).unwrap();
+        game.connect(&ids[2], connection);
+        assert!(game.players()[2].connected);
+    }
+    assert_eq!(game.session(&ids[2]).unwrap().connections, 4);
+}
+
+#[test]
+fn presence_is_visible_to_everyone() {
+    let (mut game, ids) = started_game(5);
+    game.connect(&ids[1], 1);
+    game.disconnect(&ids[1], 1);
+
+    let view = game.view(&ids[0]).unwrap();
+    let player = &view.board.players[1];
+    assert!(!player.connected);
+    assert!(player.last_seen.is_some());
+    assert!(view.board.players[0].connected);
+    assert_eq!(view.board.players[0].last_seen, None);
+
+    let view = game.spectator_view(SpectatorMode::Public);
+    assert!(!view.board.players[1].connected);
+}
+
+type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+async fn authenticate(addr: SocketAddr, auth: Value) -> (Client, String) {
+    let (mut client, _) = connect_async(format!("ws://{addr}/ws")).await.unwrap();
+    // The server expects its ping to be answered before anything else.
+    let Message::Ping(cookie) = client.next().await.unwrap().unwrap() else {
+        panic!("the server did not ping");
+    };
+    client.send(Message::Pong(cookie)).await.unwrap();
+    let msg = json!({ "Authenticate": auth }).to_string();
+    client.send(Message::Text(msg)).await.unwrap();
+    while let Some(msg) = client.next().await {
+        let Message::Text(msg) = msg.unwrap() else {
+            continue;
+        };
+        let msg: Value = serde_json::from_str(&msg).unwrap();
+        if msg["type"] == "Authenticated" {
+            let key = msg["value"]["access_key"].as_str().unwrap().to_string();
+            return (client, key);
+        }
+    }
+    panic!("the connection closed before authenticating");
+}
+
+#[tokio::test]
+async fn replaced_connections_cannot_rename_the_player() {
+    let lobby = Arc::new(Lobby::new());
+    lobby
+        .create(Config {
+            id: "table".to_string(),
+            ..Config::default()
+        })
+        .await
+        .unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(secret_hitler::serve(listener, lobby.clone()));
+
+    let (mut stale, key) = authenticate(addr, json!({ "game": "table", "user": user("p0") })).await;
+    let _current = authenticate(addr, json!({ "game": "table", "access_key": key })).await;
+
+    let rename = json!({ "EditUser": user("renamed") }).to_string();
+    stale.send(Message::Text(rename)).await.unwrap();
+    // The server handles the rename before it sees the socket close.
+    let drained = async { while let Some(Ok(_)) = stale.next().await {} };
+    let _ = tokio::time::timeout(Duration::from_secs(5), drained).await;
+
+    let game = lobby.get("table").await.unwrap();
+    assert_eq!(game.read().await.players()[0].user.name, "p0");
+}
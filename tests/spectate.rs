@@ -0,0 +1,83 @@
+mod common;
+
+use common::*;
+use rand::{rngs::StdRng, SeedableRng};
+use secret_hitler::{bot, Config, GameState, SpectatorMode, TaskAction, Viewer};
+use serde_json::{json, Value};
+
+fn roles(view: &Value) -> Vec<Value> {
+    view["board"]["players"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|p| p["role"].clone())
+        .collect()
+}
+
+#[test]
+fn public_spectators_see_no_roles() {
+    let (game, _) = started_game(7);
+    let view = serde_json::to_value(game.spectator_view(SpectatorMode::Public)).unwrap();
+    assert!(roles(&view).iter().all(Value::is_null));
+    assert_eq!(view["me"], json!(null));
+}
+
+#[test]
+fn omniscient_spectators_see_all_roles() {
+    let (game, _) = started_game(7);
+    let view = serde_json::to_value(game.spectator_view(SpectatorMode::Omniscient)).unwrap();
+    assert!(roles(&view).iter().all(|role| !role.is_null()));
+}
+
+#[test]
+fn omniscient_spectators_must_be_enabled() {
+    let game = GameState::new(Config::default());
+    assert!(game.may_spectate(SpectatorMode::Public));
+    assert!(!game.may_spectate(SpectatorMode::Omniscient));
+
+    let game = GameState::new(Config {
+        omniscient_spectators: true,
+        ..Config::default()
+    });
+    assert!(!game.may_spectate(SpectatorMode::Omniscient));
+
+    let game = GameState::new(Config {
+        omniscient_spectators: true,
+        spectator_delay: 30,
+        ..Config::default()
+    });
+    assert!(game.may_spectate(SpectatorMode::Omniscient));
+}
+
+#[test]
+fn finished_games_are_revealed_until_the_rematch() {
+    let mut game = bot_game(5, 0);
+    bot::run_bots(&mut game, &mut StdRng::seed_from_u64(0));
+    assert!(game.may_spectate(SpectatorMode::Omniscient));
+
+    let host = game.players()[0].id.clone();
+    game.on_action(host, TaskAction::Start).unwrap();
+    assert!(!game.may_spectate(SpectatorMode::Omniscient));
+}
+
+#[test]
+fn requested_views_hide_roles_until_the_game_is_over() {
+    let mut game = GameState::new(Config {
+        omniscient_spectators: true,
+        spectator_delay: 30,
+        seed: Some(0),
+        ..Config::default()
+    });
+    let host = game.add_bot(None).unwrap().id;
+    for _ in 1..5 {
+        game.add_bot(None).unwrap();
+    }
+    game.on_action(host, TaskAction::Start).unwrap();
+    let omniscient = Viewer::Spectator(SpectatorMode::Omniscient);
+    let view = serde_json::to_value(game.requested_view(&omniscient)).unwrap();
+    assert!(roles(&view).iter().all(Value::is_null));
+
+    bot::run_bots(&mut game, &mut StdRng::seed_from_u64(0));
+    let view = serde_json::to_value(game.requested_view(&omniscient)).unwrap();
+    assert!(roles(&view).iter().all(|role| !role.is_null()));
+}
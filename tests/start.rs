@@ -0,0 +1,99 @@
+mod common;
+
+use common::{started_game, user};
+use rand::{rngs::StdRng, SeedableRng};
+use secret_hitler::{
+    ActionError, Config, ExecutiveAction, GameState, Role, StartError, TaskAction,
+};
+
+#[test]
+fn start_needs_enough_players() {
+    let mut game = GameState::new(Config::default());
+    let ids = (0..3)
+        .map(|i| game.add_new_user(user(&format!("p{i}"))).unwrap().id)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        game.on_action(ids[0].clone(), TaskAction::Start),
+        Err(ActionError::CannotStart(StartError::TooFewPlayers {
+            min: 5,
+            actual: 3
+        }))
+    );
+    assert!(!game.is_running());
+}
+
+#[test]
+fn dealing_too_few_players_fails_without_a_trace() {
+    let mut game = GameState::new(Config::default());
+    for i in 0..4 {
+        game.add_new_user(user(&format!("p{i}"))).unwrap();
+    }
+    let before = game.snapshot();
+
+    assert_eq!(
+        game.start(),
+        Err(StartError::TooFewPlayers { min: 5, actual: 4 })
+    );
+    assert_eq!(game.snapshot(), before);
+    assert_eq!(ExecutiveAction::for_player_count(4), None);
+    assert_eq!(Role::roles_for(4, &mut StdRng::seed_from_u64(0)), None);
+}
+
+#[test]
+fn start_rejects_too_many_players() {
+    let mut game = GameState::new(Config::default());
+    let ids = (0..11)
+        .map(|i| game.add_new_user(user(&format!("p{i}"))).unwrap().id)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        game.on_action(ids[0].clone(), TaskAction::Start),
+        Err(ActionError::CannotStart(StartError::TooManyPlayers {
+            max: 10,
+            actual: 11
+        }))
+    );
+}
+
+#[test]
+fn start_needs_everyone_connected() {
+    let (mut game, ids) = started_game(5);
+    game.remove_player(&ids[3]);
+
+    assert_eq!(
+        game.on_action(ids[0].clone(), TaskAction::Restart),
+        Err(ActionError::CannotStart(StartError::PlayersDisconnected(
+            vec![ids[3].clone()]
+        )))
+    );
+
+    let key = game.players()[3].access_key.clone();
+    game.join_existing_user(key).unwrap();
+    assert_eq!(game.on_action(ids[0].clone(), TaskAction::Restart), Ok(()));
+}
+
+#[test]
+fn start_is_rejected_while_running() {
+    let (mut game, ids) = started_game(5);
+    let before = game.snapshot();
+
+    assert_eq!(
+        game.on_action(ids[0].clone(), TaskAction::Start),
+        Err(ActionError::CannotStart(StartError::AlreadyRunning))
+    );
+    assert_eq!(game.snapshot(), before);
+}
+
+#[test]
+fn only_the_host_may_restart() {
+    let (mut game, ids) = started_game(5);
+    assert_eq!(game.host().unwrap().id, ids[0]);
+
+    assert_eq!(
+        game.on_action(ids[1].clone(), TaskAction::Restart),
+        Err(ActionError::NotHost)
+    );
+    assert_eq!(game.on_action(ids[0].clone(), TaskAction::Restart), Ok(()));
+    assert!(game.is_running());
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::*;
+use secret_hitler::{Config, GameState, Store, TaskAction};
+
+#[tokio::test]
+async fn running_games_survive_a_restart() {
+    let dir = std::env::temp_dir().join(format!("secret-hitler-{}", rand::random::<u64>()));
+    let store = Store::new(&dir).unwrap();
+    let (mut game, ids) = started_game_with(
+        Config {
+            id: "saved".to_string(),
+            ..Config::default()
+        },
+        5,
+    );
+    elect_government(&mut game, &ids);
+    store.save(&game.snapshot()).await.unwrap();
+
+    let mut snapshots = Store::new(&dir).unwrap().load_all().await.unwrap();
+    assert_eq!(snapshots.len(), 1);
+    let mut restored = GameState::restore(snapshots.remove(0));
+    assert_eq!(restored.id(), "saved");
+    assert!(restored.is_running());
+    assert!(restored.players().iter().all(|p| !p.connected));
+
+    // The RNG continues where it left off, so both games play on alike.
+    let continue_game = |game: &mut GameState| {
+        let president = task_holder(game, &ids).unwrap().0;
+        let laws = dealt_laws(game, &president);
+        game.on_action(
+            president,
+            TaskAction::PickedLaws(laws[..2].to_vec(), laws[2]),
+        )
+        .unwrap();
+        let chancellor = task_holder(game, &ids).unwrap().0;
+        dealt_laws(game, &chancellor)
+    };
+    assert_eq!(continue_game(&mut restored), continue_game(&mut game));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+#[should_panic(expected = "escapes the data directory")]
+async fn ids_never_escape_the_data_directory() {
+    let store = Store::new(std::env::temp_dir()).unwrap();
+    let game = GameState::new(Config {
+        id: "../escaped".to_string(),
+        ..Config::default()
+    });
+    let _ = store.save(&game.snapshot()).await;
+}
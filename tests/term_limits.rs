@@ -0,0 +1,75 @@
+mod common;
+
+use common::*;
+use secret_hitler::{
+    ExecutiveAction, ExecutiveActionResponse, GameState, Law, PlayerId, Role, Task, TaskAction,
+};
+
+fn nominees(game: &GameState, ids: &[PlayerId]) -> Vec<PlayerId> {
+    match task_holder(game, ids) {
+        Some((_, Task::ChooseChancellor(options))) => options,
+        task => panic!("no president is choosing a chancellor, but {task:?}"),
+    }
+}
+
+#[test]
+fn both_members_are_term_limited_in_larger_games() {
+    let (mut game, ids) = started_game(6);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    enact(&mut game, &president, &chancellor, Law::Liberal);
+
+    let options = nominees(&game, &ids);
+    assert!(!options.contains(&president));
+    assert!(!options.contains(&chancellor));
+}
+
+#[test]
+fn only_the_chancellor_is_term_limited_with_five_alive() {
+    let (mut game, ids) = started_game(5);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    enact(&mut game, &president, &chancellor, Law::Liberal);
+
+    let options = nominees(&game, &ids);
+    assert!(options.contains(&president));
+    assert!(!options.contains(&chancellor));
+}
+
+#[test]
+fn executions_relax_term_limits() {
+    let (mut game, ids) = started_game_with(fascist_config(&[Some(ExecutiveAction::Kill)]), 6);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    enact(&mut game, &president, &chancellor, Law::Fasho);
+
+    let target = game
+        .players()
+        .iter()
+        .find(|p| p.role != Role::FashoHitler && p.id != president && p.id != chancellor)
+        .unwrap()
+        .id
+        .clone();
+    game.on_action(
+        president.clone(),
+        TaskAction::ExecuteAction(ExecutiveActionResponse::Kill(target)),
+    )
+    .unwrap();
+
+    let options = nominees(&game, &ids);
+    assert!(options.contains(&president));
+    assert!(!options.contains(&chancellor));
+}
+
+#[test]
+fn chaos_clears_term_limits() {
+    let (mut game, ids) = started_game(6);
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    enact(&mut game, &president, &chancellor, Law::Liberal);
+
+    for _ in 0..3 {
+        fail_election(&mut game, &ids);
+    }
+
+    let (next, _) = task_holder(&game, &ids).unwrap();
+    let options = nominees(&game, &ids);
+    assert_eq!(options.len(), ids.len() - 1);
+    assert!(!options.contains(&next));
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use common::*;
+use rand::thread_rng;
+use secret_hitler::{Config, SpectatorMode, StateView, Task, TaskAction, Timeouts};
+
+fn timed(timeouts: Timeouts) -> Config {
+    Config {
+        timeouts,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn no_deadline_without_timeouts() {
+    let (mut game, _) = started_game(5);
+    assert_eq!(game.deadline(), None);
+    assert!(!game.on_timeout(&mut thread_rng()));
+}
+
+#[test]
+fn afk_president_gets_a_random_chancellor() {
+    let (mut game, ids) = started_game_with(
+        timed(Timeouts {
+            choose_chancellor: Some(0),
+            ..Timeouts::default()
+        }),
+        5,
+    );
+    assert!(game.deadline().is_some());
+    let view = game.spectator_view(SpectatorMode::Public);
+    assert!(matches!(
+        view.state,
+        StateView::ChooseChancellor {
+            deadline: Some(_),
+            ..
+        }
+    ));
+
+    assert!(game.on_timeout(&mut thread_rng()));
+    assert!(ids
+        .iter()
+        .all(|id| matches!(game.tasks(id), Some(Task::Vote(_)))));
+}
+
+#[test]
+fn afk_voters_vote_nein() {
+    let (mut game, ids) = started_game_with(
+        timed(Timeouts {
+            vote: Some(0),
+            ..Timeouts::default()
+        }),
+        5,
+    );
+    let Some((president, Task::ChooseChancellor(options))) = task_holder(&game, &ids) else {
+        panic!("no president");
+    };
+    game.on_action(
+        president.clone(),
+        TaskAction::ChooseChancellor(options[0].clone()),
+    )
+    .unwrap();
+    assert!(game.on_timeout(&mut thread_rng()));
+
+    // The vote failed, so the next president nominates.
+    let (next, task) = task_holder(&game, &ids).unwrap();
+    assert!(matches!(task, Task::ChooseChancellor(_)));
+    assert_ne!(next, president);
+}
+
+#[test]
+fn afk_legislators_discard_randomly() {
+    let (mut game, ids) = started_game_with(
+        timed(Timeouts {
+            legislative: Some(0),
+            ..Timeouts::default()
+        }),
+        5,
+    );
+    let (president, chancellor) = elect_government(&mut game, &ids);
+    assert_eq!(dealt_laws(&game, &president).len(), 3);
+    assert!(game.on_timeout(&mut thread_rng()));
+    assert_eq!(dealt_laws(&game, &chancellor).len(), 2);
+    assert!(game.on_timeout(&mut thread_rng()));
+    assert!(!matches!(game.tasks(&chancellor), Some(Task::PickLaws(..))));
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use std::{sync::Arc, time::Duration};
+
+use common::*;
+use secret_hitler::{Config, GameState, GameView, SpectatorMode, Task, TaskAction};
+use tokio::{
+    sync::{watch, RwLock},
+    task::JoinHandle,
+};
+
+type Observed = Arc<std::sync::Mutex<Option<(u64, GameView)>>>;
+
+/// Follows the game like a connection does, taking its time to "send" each
+/// view. Returns the latest version and view it has seen.
+fn subscribe(game: &Arc<RwLock<GameState>>, mut updates: watch::Receiver<u64>) -> Observed {
+    let observed = Observed::default();
+    let (game, latest) = (game.clone(), observed.clone());
+    tokio::spawn(async move {
+        loop {
+            {
+                let game = game.read().await;
+                let version = *updates.borrow_and_update();
+                let view = game.spectator_view(SpectatorMode::Public);
+                *latest.lock().unwrap() = Some((version, view));
+            }
+            tokio::time::sleep(Duration::from_millis(1)).await;
+            if updates.changed().await.is_err() {
+                break;
+            }
+        }
+    });
+    observed
+}
+
+/// Waits until every subscriber has seen the current version of the game.
+async fn assert_converged(game: &Arc<RwLock<GameState>>, subscribers: &[Observed]) {
+    let expected = {
+        let game = game.read().await;
+        (game.version(), game.spectator_view(SpectatorMode::Public))
+    };
+    for _ in 0..500 {
+        if subscribers
+            .iter()
+            .all(|s| s.lock().unwrap().as_ref() == Some(&expected))
+        {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("subscribers did not converge on version {}", expected.0);
+}
+
+async fn join_all(tasks: Vec<JoinHandle<()>>) {
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn every_change_reaches_every_subscriber() {
+    let mut game = GameState::new(Config::default());
+    let ids = (0..5)
+        .map(|i| game.add_new_user(user(&format!("p{i}"))).unwrap().id)
+        .collect::<Vec<_>>();
+    let initial = game.version();
+    let game = Arc::new(RwLock::new(game));
+
+    let subscribers = (0..8)
+        .map(|_| {
+            let updates = game.try_read().unwrap().subscribe();
+            subscribe(&game, updates)
+        })
+        .collect::<Vec<_>>();
+
+    let writers = ids
+        .iter()
+        .enumerate()
+        .map(|(i, id)| {
+            let (game, id) = (game.clone(), id.clone());
+            tokio::spawn(async move {
+                for n in 0..20 {
+                    let name = user(&format!("p{i} #{n}"));
+                    game.write().await.edit_user(id.clone(), name).unwrap();
+                    tokio::task::yield_now().await;
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    join_all(writers).await;
+
+    assert_eq!(game.read().await.version(), initial + 100);
+    assert_converged(&game, &subscribers).await;
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_votes_are_all_counted() {
+    let (mut game, ids) = started_game(10);
+    let Some((president, Task::ChooseChancellor(options))) = task_holder(&game, &ids) else {
+        panic!("no president is choosing a chancellor");
+    };
+    game.on_action(president, TaskAction::ChooseChancellor(options[0].clone()))
+        .unwrap();
+    let game = Arc::new(RwLock::new(game));
+
+    let subscribers = (0..8)
+        .map(|_| {
+            let updates = game.try_read().unwrap().subscribe();
+            subscribe(&game, updates)
+        })
+        .collect::<Vec<_>>();
+
+    let voters = ids
+        .iter()
+        .map(|id| {
+            let (game, id) = (game.clone(), id.clone());
+            tokio::spawn(async move {
+                game.write()
+                    .await
+                    .on_action(id, TaskAction::Vote(true))
+                    .unwrap();
+            })
+        })
+        .collect::<Vec<_>>();
+    join_all(voters).await;
+
+    let view = game.read().await.spectator_view(SpectatorMode::Public);
+    let votes = view.board.voting_result.expect("the vote did not finish");
+    assert_eq!(votes.len(), ids.len());
+    assert_converged(&game, &subscribers).await;
+}